@@ -0,0 +1,96 @@
+//! Web Workers running a long job in batches, like a census or a predecessor
+//! search. The worker sends the progress after each batch and yields before
+//! the next one, so that the page stays responsive and can stop the job.
+
+use gloo::timers::callback::Timeout;
+use gloo_worker::{HandlerId, Worker, WorkerScope};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+/// Duration of a batch, in milliseconds.
+const BATCH_MILLIS: f64 = 100.0;
+
+/// A job run by a `BatchWorker`.
+pub trait Job: 'static {
+  /// Sent by the page to start the job.
+  type Start: Serialize + DeserializeOwned;
+  /// Sent back to the page after each batch.
+  type Progress: Serialize + DeserializeOwned;
+
+  fn start(start: Self::Start) -> Self;
+
+  /// Works on the job while `in_time()` is true, and returns the progress so
+  /// far and whether the job is done.
+  fn run_batch(&mut self, in_time: impl Fn() -> bool) -> (Self::Progress, bool);
+}
+
+#[derive(Serialize, Deserialize)]
+pub enum Input<S> {
+  /// Starts a job, replacing the current one if any.
+  Start(S),
+  Stop,
+}
+
+pub struct BatchWorker<J> {
+  /// Current job, and the page it reports to.
+  job: Option<(HandlerId, J)>,
+  next_batch: Option<Timeout>,
+}
+
+pub enum Msg {
+  Batch,
+}
+
+impl<J: Job> BatchWorker<J> {
+  fn schedule_batch(&mut self, scope: &WorkerScope<Self>) {
+    let scope = scope.clone();
+    self.next_batch = Some(Timeout::new(0, move || scope.send_message(Msg::Batch)));
+  }
+
+  fn run_batch(&mut self, scope: &WorkerScope<Self>) {
+    let (page, job) = match &mut self.job {
+      Some(job) => job,
+      None => return,
+    };
+    let started = js_sys::Date::now();
+    let (progress, done) = job.run_batch(|| js_sys::Date::now() - started < BATCH_MILLIS);
+    scope.respond(*page, progress);
+    if done {
+      self.job = None;
+    } else {
+      self.schedule_batch(scope);
+    }
+  }
+}
+
+impl<J: Job> Worker for BatchWorker<J> {
+  type Message = Msg;
+  type Input = Input<J::Start>;
+  type Output = J::Progress;
+
+  fn create(_: &WorkerScope<Self>) -> Self {
+    Self {
+      job: None,
+      next_batch: None,
+    }
+  }
+
+  fn update(&mut self, scope: &WorkerScope<Self>, msg: Self::Message) {
+    match msg {
+      Msg::Batch => self.run_batch(scope),
+    }
+  }
+
+  fn received(&mut self, scope: &WorkerScope<Self>, msg: Self::Input, id: HandlerId) {
+    match msg {
+      Input::Start(start) => {
+        self.job = Some((id, J::start(start)));
+        self.schedule_batch(scope);
+      }
+      Input::Stop => {
+        self.job = None;
+        self.next_batch = None;
+      }
+    }
+  }
+}
@@ -0,0 +1,54 @@
+//! Builds `src/lexicon/lexicon.bin` from the text version of the
+//! [Life Lexicon](https://conwaylife.com/ref/lexicon/), vendored in
+//! `src/lexicon/lexicon.txt`:
+//!
+//! ```sh
+//! cargo run --bin build-lexicon -- src/lexicon/lexicon.txt src/lexicon/lexicon.bin
+//! ```
+//!
+//! The same source always produces the same file.
+
+use std::process;
+use yew_app::lexicon::parse_lexicon;
+
+fn main() {
+  let args: Vec<String> = std::env::args().skip(1).collect();
+  let (source_path, output_path) = match args.as_slice() {
+    [source_path, output_path] => (source_path, output_path),
+    _ => {
+      eprintln!("Usage: build-lexicon <lexicon.txt> <lexicon.bin>");
+      process::exit(2);
+    }
+  };
+
+  // The text lexicon is distributed in Latin-1.
+  let source: String = match std::fs::read(source_path) {
+    Ok(bytes) => bytes.iter().map(|&b| b as char).collect(),
+    Err(error) => {
+      eprintln!("Error: could not read {}: {}", source_path, error);
+      process::exit(1);
+    }
+  };
+
+  let (lexicon, errors) = parse_lexicon(&source);
+  for error in &errors {
+    eprintln!("Warning: skipped entry at {}", error);
+  }
+
+  let serialized = bincode::serialize(&lexicon).expect("lexicon to be serializable");
+  if let Err(error) = std::fs::write(output_path, serialized) {
+    eprintln!("Error: could not write {}: {}", output_path, error);
+    process::exit(1);
+  }
+  eprintln!(
+    "Wrote {} terms ({} with a pattern) to {}, {} entries skipped",
+    lexicon.terms.len(),
+    lexicon
+      .terms
+      .iter()
+      .filter(|term| !term.cells.is_empty())
+      .count(),
+    output_path,
+    errors.len()
+  );
+}
@@ -0,0 +1,8 @@
+//! Web Worker running a census of soups, spawned by the page (see `census`).
+
+use gloo_worker::Registrable;
+use yew_app::census::worker::CensusWorker;
+
+fn main() {
+  CensusWorker::registrar().register();
+}
@@ -0,0 +1,8 @@
+//! Web Worker rendering animated GIFs, spawned by the page (see `export`).
+
+use gloo_worker::Registrable;
+use yew_app::export::worker::ExportWorker;
+
+fn main() {
+  ExportWorker::registrar().register();
+}
@@ -0,0 +1,106 @@
+//! Runs a census of random soups outside of the browser, counting the objects
+//! they leave by apgcode.
+//!
+//! ```sh
+//! cargo run --release --bin lifeee-census -- 10000 census.csv --symmetry D2_+1
+//! ```
+
+use std::process;
+use yew_app::census::Census;
+use yew_app::soup::{SoupOptions, Symmetry};
+
+const USAGE: &str = "Usage: lifeee-census <soups> [output.csv|output.json] [options]
+
+Prints the census as CSV when no output file is given.
+
+Options:
+  --prefix <TEXT>      prefix of the seeds of the soups (default: census_)
+  --symmetry <NAME>    symmetry of the soups, e.g. C1, C4_1, D2_+1 (default: C1)
+  --size <N>           width and height of the soups (default: 16)
+  --density <PERCENT>  density of the soups (default: 50)";
+
+struct Args {
+  soups: u64,
+  output: Option<String>,
+  prefix: String,
+  options: SoupOptions,
+}
+
+fn parse_args() -> Result<Args, String> {
+  let mut args = std::env::args().skip(1);
+  let mut positional = vec![];
+  let mut prefix = "census_".to_string();
+  let mut options = SoupOptions::default();
+  while let Some(arg) = args.next() {
+    let mut value = |name: &str| args.next().ok_or(format!("{} expects a value", name));
+    match arg.as_str() {
+      "--prefix" => prefix = value("--prefix")?,
+      "--symmetry" => {
+        let name = value("--symmetry")?;
+        options.symmetry = *Symmetry::ALL
+          .iter()
+          .find(|symmetry| symmetry.label().eq_ignore_ascii_case(&name))
+          .ok_or(format!("unknown symmetry {}", name))?;
+      }
+      "--size" => {
+        options.size = value("--size")?
+          .parse()
+          .ok()
+          .filter(|&size| size > 0)
+          .ok_or("--size expects a positive number")?
+      }
+      "--density" => {
+        let percent: f64 = value("--density")?
+          .parse()
+          .ok()
+          .filter(|percent| (0.0..=100.0).contains(percent))
+          .ok_or("--density expects a percentage")?;
+        options.density = percent / 100.0;
+      }
+      "-h" | "--help" => return Err(USAGE.to_string()),
+      _ if arg.starts_with("--") => return Err(format!("unknown option {}", arg)),
+      _ => positional.push(arg),
+    }
+  }
+  let (soups, output) = match positional.as_slice() {
+    [soups] => (soups, None),
+    [soups, output] => (soups, Some(output.clone())),
+    _ => return Err(USAGE.to_string()),
+  };
+  Ok(Args {
+    soups: soups
+      .parse()
+      .map_err(|_| "the number of soups must be a positive number")?,
+    output,
+    prefix,
+    options,
+  })
+}
+
+fn main() {
+  let args = parse_args().unwrap_or_else(|message| {
+    eprintln!("{}", message);
+    process::exit(2);
+  });
+  let mut census = Census::new(args.options);
+  for index in 0..args.soups {
+    census.add_soup(&Census::seed(&args.prefix, index));
+    if (index + 1).is_multiple_of(1000) {
+      eprintln!("{} soups", index + 1);
+    }
+  }
+  match &args.output {
+    Some(output) => {
+      let content = if output.ends_with(".json") {
+        census.to_json()
+      } else {
+        census.to_csv()
+      };
+      if let Err(error) = std::fs::write(output, content) {
+        eprintln!("Error: could not write {}: {}", output, error);
+        process::exit(1);
+      }
+    }
+    None => print!("{}", census.to_csv()),
+  }
+}
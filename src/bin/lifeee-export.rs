@@ -0,0 +1,181 @@
+//! Renders a pattern of the lexicon to a PNG or SVG image, or to an animated
+//! GIF/APNG, outside of the browser.
+//!
+//! ```sh
+//! cargo run --bin lifeee-export -- glider glider.gif --generations 8
+//! ```
+
+use std::collections::VecDeque;
+use std::process;
+use yew_app::color_utils::from_hex;
+use yew_app::export::*;
+use yew_app::lexicon::Lexicon;
+use yew_app::life::{from_term, tick, CellSet};
+use yew_app::settings::default_settings;
+use yew_app::theme::{Preset, ThemeColor};
+
+const USAGE: &str =
+  "Usage: lifeee-export <pattern> <output.png|output.svg|output.gif|output.apng> [options]
+
+Options:
+  --from <N>         first generation to render (default: 0)
+  --generations <N>  number of generations to animate (default: 50)
+  --trail <N>        number of previous generations to show (default: 0)
+  --cell-size <PX>   size of a cell, in pixels (default: 10)
+  --delay <MS>       delay between frames, in milliseconds (default: 100)
+  --no-grid          don't draw the grid
+  --theme <NAME>     colors of the board: light, dark, high-contrast or
+                     golly-classic (default: light)
+  --color <PART>=<#RRGGBB>
+                     changes one color of the theme: background, grid,
+                     live-cells, recent-trail or old-trail";
+
+/// Name of a preset or of a color of a theme on the command line, e.g.
+/// `high-contrast` for “High contrast”.
+fn option_name(label: &str) -> String {
+  label.to_lowercase().replace(' ', "-")
+}
+
+struct Args {
+  pattern: String,
+  output: String,
+  from: u32,
+  generations: u32,
+  trail: usize,
+  options: ExportOptions,
+}
+
+fn parse_args() -> Result<Args, String> {
+  let mut args = std::env::args().skip(1);
+  let mut positional = vec![];
+  let mut from = 0;
+  let mut generations = 50;
+  let mut trail = 0;
+  let mut options = ExportOptions::default();
+  // Colors given with `--color`, applied to the theme once it is known.
+  let mut colors = vec![];
+  while let Some(arg) = args.next() {
+    let mut value = |name: &str| args.next().ok_or(format!("{} expects a value", name));
+    let number = |name: &str, value: String| {
+      value
+        .parse::<u32>()
+        .map_err(|_| format!("{} expects a positive number", name))
+    };
+    match arg.as_str() {
+      "--from" => from = number("--from", value("--from")?)?,
+      "--generations" => generations = number("--generations", value("--generations")?)?,
+      "--trail" => trail = number("--trail", value("--trail")?)? as usize,
+      "--cell-size" => options.cell_size = number("--cell-size", value("--cell-size")?)?,
+      "--delay" => {
+        options.frame_delay = number("--delay", value("--delay")?)?.min(u16::MAX as u32) as u16
+      }
+      "--no-grid" => options.grid = false,
+      "--theme" => {
+        let name = value("--theme")?;
+        options.theme = Preset::ALL
+          .iter()
+          .find(|preset| option_name(preset.label()) == name.to_lowercase())
+          .ok_or(format!("unknown theme {}", name))?
+          .theme();
+      }
+      "--color" => {
+        let color = value("--color")?;
+        let (part, hex) = color
+          .split_once('=')
+          .ok_or("--color expects <part>=<#RRGGBB>")?;
+        let part = *ThemeColor::ALL
+          .iter()
+          .find(|part_color| option_name(part_color.label()) == part.to_lowercase())
+          .ok_or(format!("unknown color {}", part))?;
+        let rgb = from_hex(hex).ok_or(format!("{} is not a color like #1a2b3c", hex))?;
+        colors.push((part, rgb));
+      }
+      "-h" | "--help" => return Err(USAGE.to_string()),
+      _ if arg.starts_with("--") => return Err(format!("unknown option {}", arg)),
+      _ => positional.push(arg),
+    }
+  }
+  for (part, rgb) in colors {
+    options.theme = options.theme.with_color(part, rgb);
+  }
+  match positional.as_slice() {
+    [pattern, output] => Ok(Args {
+      pattern: pattern.clone(),
+      output: output.clone(),
+      from,
+      generations,
+      trail,
+      options,
+    }),
+    _ => Err(USAGE.to_string()),
+  }
+}
+
+fn render(args: &Args) -> Result<Vec<u8>, String> {
+  let lexicon = Lexicon::get();
+  let term = lexicon
+    .terms
+    .iter()
+    .find(|term| term.name.eq_ignore_ascii_case(&args.pattern) && !term.cells.is_empty())
+    .ok_or(format!(
+      "no pattern named “{}” in the lexicon",
+      args.pattern
+    ))?;
+  let cells = from_term(term);
+  let generations = args.from..args.from + args.generations;
+
+  let result = if args.output.ends_with(".gif") {
+    render_animation(
+      &cells,
+      generations,
+      args.trail,
+      &args.options,
+      AnimationFormat::Gif,
+    )
+  } else if args.output.ends_with(".apng") {
+    render_animation(
+      &cells,
+      generations,
+      args.trail,
+      &args.options,
+      AnimationFormat::Apng,
+    )
+  } else {
+    let mut current = cells;
+    let mut previous_gens: VecDeque<CellSet> = VecDeque::new();
+    for _ in 0..args.from {
+      previous_gens.push_front(current.clone());
+      previous_gens.truncate(args.trail);
+      current = tick(&current);
+    }
+    let previous_gens: Vec<CellSet> = previous_gens.into_iter().collect();
+    if args.output.ends_with(".svg") {
+      let svg = render_svg(
+        &current,
+        &previous_gens,
+        SvgScope::BoundingBox,
+        &args.options,
+        &default_settings(),
+      );
+      Ok(svg.into_bytes())
+    } else {
+      render_png(&current, &previous_gens, &args.options)
+    }
+  };
+  result.map_err(|error| error.to_string())
+}
+
+fn main() {
+  let args = parse_args().unwrap_or_else(|message| {
+    eprintln!("{}", message);
+    process::exit(2);
+  });
+  let bytes = render(&args).unwrap_or_else(|message| {
+    eprintln!("Error: {}", message);
+    process::exit(1);
+  });
+  if let Err(error) = std::fs::write(&args.output, bytes) {
+    eprintln!("Error: could not write {}: {}", args.output, error);
+    process::exit(1);
+  }
+}
@@ -0,0 +1,9 @@
+//! Web Worker searching for a predecessor of a pattern, spawned by the page
+//! (see `predecessor`).
+
+use gloo_worker::Registrable;
+use yew_app::predecessor::worker::PredecessorWorker;
+
+fn main() {
+  PredecessorWorker::registrar().register();
+}
@@ -0,0 +1,8 @@
+//! Web Worker running the simulation, spawned by the page (see `engine`).
+
+use gloo_worker::Registrable;
+use yew_app::engine::Engine;
+
+fn main() {
+  Engine::registrar().register();
+}
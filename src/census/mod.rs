@@ -0,0 +1,249 @@
+//! Census of the objects left by random soups, like apgsearch does: each soup
+//! is run until it stabilises, then its ash is separated into objects, each
+//! one named by its apgcode and tallied. Used both by the web UI, in a worker,
+//! and natively by the `lifeee-census` binary.
+
+pub mod worker;
+
+use crate::life::apgcode::{apgcode, period, MAX_PERIOD};
+use crate::life::objects::separate;
+use crate::life::{tick, CellSet};
+use crate::soup::{soup, SoupOptions};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Soups not stabilised after this many generations are counted as
+/// `PATHOLOGICAL`, like in apgsearch.
+pub const MAX_GENERATIONS: u32 = 10000;
+/// The population must repeat with a period of at most this many generations
+/// for the soup to be considered stable.
+const MAX_ASH_PERIOD: usize = 30;
+/// Generations the population must keep repeating for.
+const STABLE_WINDOW: usize = 4 * MAX_ASH_PERIOD;
+/// Name counted for soups that don't stabilise.
+pub const PATHOLOGICAL: &str = "PATHOLOGICAL";
+/// Name counted for objects without an apgcode, e.g. ones with a period longer
+/// than `MAX_PERIOD`.
+pub const UNIDENTIFIED: &str = "zz_UNIDENTIFIED";
+
+/// Whether the last `STABLE_WINDOW` populations repeat with a period of at
+/// most `MAX_ASH_PERIOD`.
+fn is_periodic(populations: &[usize]) -> bool {
+  if populations.len() < STABLE_WINDOW + MAX_ASH_PERIOD {
+    return false;
+  }
+  let start = populations.len() - STABLE_WINDOW;
+  (1..=MAX_ASH_PERIOD)
+    .any(|period| (start..populations.len()).all(|i| populations[i] == populations[i - period]))
+}
+
+/// Runs the cells until their population becomes periodic, returning the ash,
+/// or `None` if it doesn't within `MAX_GENERATIONS`.
+pub fn stabilize(cells: &CellSet) -> Option<CellSet> {
+  let mut cells = cells.clone();
+  let mut populations = vec![cells.len()];
+  for generation in 1..=MAX_GENERATIONS {
+    cells = tick(&cells);
+    populations.push(cells.len());
+    if (generation as usize).is_multiple_of(MAX_ASH_PERIOD) && is_periodic(&populations) {
+      return Some(cells);
+    }
+  }
+  None
+}
+
+/// Whether the objects evolve the same together as each on its own, during
+/// `generations` generations.
+fn are_independent(objects: &[CellSet], generations: u32) -> bool {
+  let mut together: CellSet = objects.iter().flatten().copied().collect();
+  let mut apart = objects.to_vec();
+  for _ in 0..generations {
+    together = tick(&together);
+    for object in &mut apart {
+      *object = tick(object);
+    }
+    if apart.iter().map(CellSet::len).sum::<usize>() != together.len()
+      || !apart.iter().flatten().all(|cell| together.contains(cell))
+    {
+      return false;
+    }
+  }
+  true
+}
+
+/// Names of the objects of the ash. Cells at most 2 cells apart are first
+/// grouped, so that e.g. the two halves of a spaceship stay together; a group
+/// is then split into the objects touching each other's cells if each one is
+/// periodic on its own and they don't interact, like the two blocks of a
+/// bi-block.
+pub fn identify(ash: &CellSet) -> Vec<String> {
+  let mut names = vec![];
+  for group in separate(ash, 2) {
+    let parts = separate(&group, 1);
+    let periods: Option<Vec<u32>> = parts.iter().map(|part| Some(period(part)?.0)).collect();
+    let split = parts.len() > 1
+      && periods.is_some_and(|periods| {
+        let longest = periods.iter().copied().max().unwrap_or(1);
+        are_independent(&parts, 2 * longest.min(MAX_PERIOD))
+      });
+    let objects = if split { parts } else { vec![group] };
+    names.extend(
+      objects
+        .iter()
+        .map(|object| apgcode(object).unwrap_or_else(|| UNIDENTIFIED.to_string())),
+    );
+  }
+  names
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Tally {
+  pub count: u64,
+  /// Seed of the first soup the object was found in.
+  pub soup: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Census {
+  pub options: SoupOptions,
+  /// Number of soups run.
+  pub soups: u64,
+  /// Objects found, by apgcode.
+  pub objects: HashMap<String, Tally>,
+}
+
+/// Row of the exported census.
+#[derive(Serialize)]
+struct Row<'a> {
+  apgcode: &'a str,
+  count: u64,
+  soup: &'a str,
+}
+
+#[derive(Serialize)]
+struct Export<'a> {
+  symmetry: &'a str,
+  size: u32,
+  density: f64,
+  soups: u64,
+  objects: Vec<Row<'a>>,
+}
+
+impl Census {
+  pub fn new(options: SoupOptions) -> Self {
+    Self {
+      options,
+      soups: 0,
+      objects: HashMap::new(),
+    }
+  }
+
+  /// Seed of the soup number `index` of a census, so that any soup can be
+  /// made again from the prefix.
+  pub fn seed(prefix: &str, index: u64) -> String {
+    format!("{}{}", prefix, index)
+  }
+
+  fn count(&mut self, name: String, seed: &str) {
+    self
+      .objects
+      .entry(name)
+      .or_insert_with(|| Tally {
+        count: 0,
+        soup: seed.to_string(),
+      })
+      .count += 1;
+  }
+
+  /// Runs the soup made from the seed, and counts the objects it leaves.
+  pub fn add_soup(&mut self, seed: &str) {
+    self.soups += 1;
+    match stabilize(&soup(seed, &self.options)) {
+      Some(ash) => {
+        for name in identify(&ash) {
+          self.count(name, seed);
+        }
+      }
+      None => self.count(PATHOLOGICAL.to_string(), seed),
+    }
+  }
+
+  /// Objects found, the most common first.
+  pub fn sorted(&self) -> Vec<(&String, &Tally)> {
+    let mut objects: Vec<_> = self.objects.iter().collect();
+    objects.sort_by(|(a, tally_a), (b, tally_b)| tally_b.count.cmp(&tally_a.count).then(a.cmp(b)));
+    objects
+  }
+
+  pub fn to_csv(&self) -> String {
+    let mut csv = "apgcode,count,soup\n".to_string();
+    for (apgcode, tally) in self.sorted() {
+      csv.push_str(&format!("{},{},{}\n", apgcode, tally.count, tally.soup));
+    }
+    csv
+  }
+
+  pub fn to_json(&self) -> String {
+    let export = Export {
+      symmetry: self.options.symmetry.label(),
+      size: self.options.size,
+      density: self.options.density,
+      soups: self.soups,
+      objects: self
+        .sorted()
+        .into_iter()
+        .map(|(apgcode, tally)| Row {
+          apgcode,
+          count: tally.count,
+          soup: &tally.soup,
+        })
+        .collect(),
+    };
+    serde_json::to_string_pretty(&export).unwrap()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::life::cells;
+
+  #[test]
+  fn identifies_the_objects_of_the_ash() {
+    let block = [(0, 0), (1, 0), (0, 1), (1, 1)];
+    let blinker = [(10, 0), (10, 1), (10, 2)];
+    // Two blocks one cell apart: a pseudo still life, counted as two blocks.
+    let other_block = [(3, 0), (4, 0), (3, 1), (4, 1)];
+    let lwss = [
+      (21, 0),
+      (24, 0),
+      (20, 1),
+      (20, 2),
+      (24, 2),
+      (20, 3),
+      (21, 3),
+      (22, 3),
+      (23, 3),
+    ];
+    let ash = cells(&[&block[..], &blinker, &other_block, &lwss].concat());
+    let mut names = identify(&ash);
+    names.sort();
+    assert_eq!(names, vec!["xp2_7", "xq4_6frc", "xs4_33", "xs4_33"]);
+  }
+
+  #[test]
+  fn census_counts_the_objects_of_each_soup() {
+    let mut census = Census::new(SoupOptions::default());
+    for index in 0..3 {
+      census.add_soup(&Census::seed("test", index));
+    }
+    assert_eq!(census.soups, 3);
+    assert!(census.objects.contains_key("xs4_33"));
+    assert!(stabilize(&cells(&[(0, 0), (1, 0), (0, 1), (1, 1)])).is_some());
+    let csv = census.to_csv();
+    assert!(csv.starts_with("apgcode,count,soup\n"));
+    assert_eq!(csv.lines().count(), census.objects.len() + 1);
+    let json: serde_json::Value = serde_json::from_str(&census.to_json()).unwrap();
+    assert_eq!(json["soups"], 3);
+  }
+}
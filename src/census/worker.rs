@@ -0,0 +1,62 @@
+//! Census run in a Web Worker, a batch of soups at a time (see `batch`).
+
+use super::Census;
+use crate::batch::{self, BatchWorker, Job};
+use crate::soup::SoupOptions;
+use serde::{Deserialize, Serialize};
+
+/// Path of the script of the worker, built by Trunk from
+/// `src/bin/census-worker.rs`.
+pub const CENSUS_WORKER_PATH: &str = "/census-worker.js";
+
+pub type CensusWorker = BatchWorker<CensusJob>;
+pub type Input = batch::Input<Start>;
+
+/// Starts a census of `soups` soups, whose seeds are `prefix` followed by
+/// their number.
+#[derive(Serialize, Deserialize)]
+pub struct Start {
+  pub prefix: String,
+  pub soups: u64,
+  pub options: SoupOptions,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Progress {
+  pub census: Census,
+  /// Whether all the soups were run.
+  pub done: bool,
+}
+
+pub struct CensusJob {
+  prefix: String,
+  /// Number of soups to run.
+  total: u64,
+  census: Census,
+}
+
+impl Job for CensusJob {
+  type Start = Start;
+  type Progress = Progress;
+
+  fn start(start: Start) -> Self {
+    CensusJob {
+      prefix: start.prefix,
+      total: start.soups,
+      census: Census::new(start.options),
+    }
+  }
+
+  fn run_batch(&mut self, in_time: impl Fn() -> bool) -> (Progress, bool) {
+    while self.census.soups < self.total && in_time() {
+      let seed = Census::seed(&self.prefix, self.census.soups);
+      self.census.add_soup(&seed);
+    }
+    let done = self.census.soups >= self.total;
+    let progress = Progress {
+      census: self.census.clone(),
+      done,
+    };
+    (progress, done)
+  }
+}
@@ -1,7 +1,45 @@
+pub type Rgb = [u8; 3];
+
+pub const BACKGROUND_COLOR: Rgb = [0xff, 0xff, 0xff];
+pub const GRID_GREY: f64 = 0.9;
+pub const LIVE_CELL_COLOR: Rgb = [0x0d, 0x00, 0x8b];
+pub const BIRTH_COLOR: Rgb = [0x2e, 0x9d, 0x3e];
+pub const HEAT_COLD_COLOR: Rgb = [0xff, 0xe0, 0x82];
+pub const HEAT_HOT_COLOR: Rgb = [0xc6, 0x28, 0x28];
+pub const STOP_REGION_COLOR: Rgb = [0xf5, 0x9f, 0x00];
+pub const OBJECT_COLOR: Rgb = [0x00, 0x89, 0x7b];
+pub const PARENT_COLOR: Rgb = [0xd8, 0x1b, 0x60];
+
 pub fn grey(coeff: f64) -> String {
+  to_hex(grey_rgb(coeff))
+}
+
+pub fn grey_rgb(coeff: f64) -> Rgb {
   let coeff = f64::min(f64::max(coeff, 0.0), 1.0);
   let v = (coeff * 255.0) as u8;
-  format!("#{:0>2x}{:0>2x}{:0>2x}", v, v, v)
+  [v, v, v]
+}
+
+pub fn to_hex([r, g, b]: Rgb) -> String {
+  format!("#{:0>2x}{:0>2x}{:0>2x}", r, g, b)
+}
+
+/// Parses a color written like `to_hex` writes it, e.g. the value of a color
+/// input.
+pub fn from_hex(hex: &str) -> Option<Rgb> {
+  let hex = hex.strip_prefix('#')?;
+  if hex.len() != 6 || !hex.is_ascii() {
+    return None;
+  }
+  let channel = |i: usize| u8::from_str_radix(&hex[2 * i..2 * i + 2], 16).ok();
+  Some([channel(0)?, channel(1)?, channel(2)?])
+}
+
+/// Color `coeff` of the way from `from` to `to`.
+pub fn mix(from: Rgb, to: Rgb, coeff: f64) -> Rgb {
+  let coeff = coeff.clamp(0.0, 1.0);
+  let channel = |i: usize| (from[i] as f64 + (to[i] as f64 - from[i] as f64) * coeff).round() as u8;
+  [channel(0), channel(1), channel(2)]
 }
 
 #[cfg(test)]
@@ -32,4 +70,28 @@ mod tests {
   fn grey_1_5_returns_white() {
     assert_eq!(grey(1.5), "#ffffff".to_string())
   }
+
+  #[test]
+  fn mix_goes_from_one_color_to_the_other() {
+    assert_eq!(
+      mix(BACKGROUND_COLOR, LIVE_CELL_COLOR, 0.0),
+      BACKGROUND_COLOR
+    );
+    assert_eq!(mix(BACKGROUND_COLOR, LIVE_CELL_COLOR, 1.0), LIVE_CELL_COLOR);
+    assert_eq!(mix([0, 0, 0], [255, 255, 255], 0.5), [128, 128, 128]);
+  }
+
+  #[test]
+  fn to_hex_formats_live_cell_color() {
+    assert_eq!(to_hex(LIVE_CELL_COLOR), "#0d008b".to_string())
+  }
+
+  #[test]
+  fn from_hex_parses_what_to_hex_formats() {
+    assert_eq!(from_hex("#0d008b"), Some(LIVE_CELL_COLOR));
+    assert_eq!(from_hex("#FFFFFF"), Some(BACKGROUND_COLOR));
+    assert_eq!(from_hex("0d008b"), None);
+    assert_eq!(from_hex("#0d00"), None);
+    assert_eq!(from_hex("#0d00é"), None);
+  }
 }
@@ -0,0 +1,89 @@
+use crate::lexicon::Term;
+use crate::library::term_from_cells;
+use crate::life::apgcode::{apgcode, decode};
+use wasm_bindgen::JsCast;
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+/// Loads an object onto the board from its apgcode, typed or pasted.
+pub struct ApgcodeInput {
+  code: String,
+  error: Option<String>,
+}
+
+#[derive(Properties, PartialEq)]
+pub struct Props {
+  pub on_apply_pattern: Callback<Term>,
+}
+
+pub enum Msg {
+  ChangeCode(String),
+  Load,
+}
+
+impl Component for ApgcodeInput {
+  type Message = Msg;
+  type Properties = Props;
+
+  fn create(_: &Context<Self>) -> Self {
+    Self {
+      code: String::new(),
+      error: None,
+    }
+  }
+
+  fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+    match msg {
+      Msg::ChangeCode(code) => {
+        self.code = code;
+        self.error = None;
+      }
+      Msg::Load => match decode(&self.code) {
+        Ok(cells) => {
+          // Named by its canonical code, whatever phase or orientation was
+          // typed.
+          let name = apgcode(&cells).unwrap_or_else(|| self.code.trim().to_string());
+          let term = term_from_cells(
+            name,
+            format!("Object loaded from the apgcode {}", self.code.trim()),
+            vec!["apgcode".to_string()],
+            &cells,
+            None,
+          );
+          ctx.props().on_apply_pattern.emit(term);
+        }
+        Err(error) => self.error = Some(error),
+      },
+    }
+    true
+  }
+
+  fn view(&self, ctx: &Context<Self>) -> Html {
+    let on_input = ctx.link().callback(|event: InputEvent| {
+      let input = event
+        .target()
+        .and_then(|t| t.dyn_into::<HtmlInputElement>().ok())
+        .unwrap();
+      Msg::ChangeCode(input.value())
+    });
+    let on_keydown = ctx
+      .link()
+      .batch_callback(|event: KeyboardEvent| (event.key() == "Enter").then_some(Msg::Load));
+
+    html! {
+      <div class="apgcode-input">
+        <input
+          type="text"
+          placeholder="apgcode, e.g. xq4_153"
+          value={self.code.clone()}
+          oninput={on_input}
+          onkeydown={on_keydown}
+        />
+        <button disabled={self.code.trim().is_empty()} onclick={ctx.link().callback(|_| Msg::Load)}>
+          {"Load"}
+        </button>
+        {for self.error.iter().map(|error| html! { <div class="hint">{error.clone()}</div> })}
+      </div>
+    }
+  }
+}
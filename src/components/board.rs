@@ -1,26 +1,81 @@
-use crate::color_utils::grey;
+use crate::color_utils::{
+  to_hex, Rgb, BACKGROUND_COLOR, OBJECT_COLOR, PARENT_COLOR, STOP_REGION_COLOR,
+};
+use crate::export::RgbaImage;
+use crate::lexicon::*;
 use crate::life;
+use crate::life::analysis::Object;
+use crate::render::color_mode::ColorMode;
+use crate::render::{canvas, pixels};
 use crate::settings::Settings;
-use lexicon::*;
+use crate::theme::Theme;
+use crate::viewport::{grid_width, pitch, to_device_pixels, MIN_ZOOM};
+use gloo::events::EventListener;
+use gloo::timers::callback::Timeout;
+use std::collections::HashMap;
 use wasm_bindgen::*;
 use web_sys::WheelEvent;
 use yew::prelude::*;
 
+/// Pointer moves smaller than this (in pixels) between press and release
+/// still count as a click.
+const CLICK_TOLERANCE: i32 = 3;
+/// Holding the pointer still for this long starts drawing cells.
+const LONG_PRESS_MILLIS: u32 = 500;
+/// Two taps closer in time than this make a double tap.
+const DOUBLE_TAP_MILLIS: f64 = 300.0;
+/// Zoom factor applied by a double tap.
+const DOUBLE_TAP_ZOOM: f64 = 2.0;
+
 #[derive(PartialEq, Properties)]
 pub struct BoardProps {
   pub cells: life::CellSet,
   pub previous_gens: Vec<life::CellSet>,
+  pub color_mode: ColorMode,
   pub offset: (f64, f64),
   pub zoom: f64,
   pub move_offset: Callback<(f64, f64)>,
   pub change_zoom: Callback<(i32, i32, f64)>,
   pub width: u32,
   pub height: u32,
+  /// Region selected with Shift + drag, top-left and bottom-right cells.
+  pub selection: Option<(Cell, Cell)>,
+  pub select_region: Callback<Option<(Cell, Cell)>>,
+  /// Called when the board is clicked without being dragged.
+  pub click_cell: Callback<Cell>,
+  /// Called for each cell drawn after a long press, with whether it must be
+  /// made alive or dead.
+  pub draw_cell: Callback<(Cell, bool)>,
+  /// Regions watched by the stop conditions, outlined.
+  pub stop_regions: Vec<(Cell, Cell)>,
+  /// Objects outlined and labelled.
+  pub objects: Vec<Object>,
+  /// Parent generation found by the predecessor search, outlined over the
+  /// board.
+  pub parent: Option<life::CellSet>,
 }
 
 pub struct Board {
   canvas_ref: NodeRef,
+  /// Board drawn in memory before being copied to the canvas, kept between
+  /// renders to avoid reallocating it.
+  image: RgbaImage,
+  /// Positions of the pointers currently pressed, by pointer id.
+  pointers: HashMap<i32, (i32, i32)>,
   last_offset: Option<(f64, f64)>,
+  selection_start: Option<Cell>,
+  pointer_down_at: Option<(i32, i32)>,
+  long_press: Option<Timeout>,
+  /// While drawing, the state given to the cells and the last cell drawn.
+  drawing: Option<(bool, Cell)>,
+  /// Position and time stamp of the last tap, to detect double taps.
+  last_tap: Option<(i32, i32, f64)>,
+  /// Device pixels per CSS pixel. The canvas has this many pixels for each
+  /// CSS pixel of its size, so that it is sharp on high-DPI screens.
+  pixel_ratio: f64,
+  /// Listens for the pixel ratio to change, e.g. when the window moves to
+  /// another monitor.
+  _pixel_ratio_handle: Option<EventListener>,
 }
 
 impl Board {
@@ -41,96 +96,119 @@ impl Board {
       .unwrap()
   }
 
-  fn cell_range(
+  /// Offset and settings the board is drawn with, in device pixels.
+  fn device_pixels(&self, ctx: &Context<Self>) -> ((f64, f64), Settings) {
+    to_device_pixels(ctx.props().offset, &self.settings(ctx), self.pixel_ratio)
+  }
+
+  /// Cell under the pointer at `(x, y)`, in CSS pixels.
+  fn cell_at(&self, ctx: &Context<Self>, x: i32, y: i32) -> Cell {
+    let (offset, settings) = self.device_pixels(ctx);
+    let zoom = ctx.props().zoom;
+    let (pitch, grid_width) = (pitch(zoom, &settings), grid_width(zoom, &settings));
+    let (x, y) = (x as f64 * self.pixel_ratio, y as f64 * self.pixel_ratio);
+    Cell {
+      x: ((x - offset.0 - grid_width) / pitch).floor() as i32,
+      y: ((y - offset.1 - grid_width) / pitch).floor() as i32,
+    }
+  }
+
+  fn draw_selection(
     &self,
     settings: &Settings,
+    (min, max): (Cell, Cell),
     offset: (f64, f64),
     zoom: f64,
-  ) -> (std::ops::Range<i32>, std::ops::Range<i32>) {
-    let canvas = self.canvas();
-
-    let from_x = self.size_to_cells(settings, -offset.0, zoom) as i32 - 1;
-    let to_x = from_x + self.size_to_cells(settings, canvas.width() as f64, zoom) as i32 + 1;
-    let from_y = self.size_to_cells(settings, -offset.1, zoom) as i32 - 1;
-    let to_y = from_y + self.size_to_cells(settings, canvas.height() as f64, zoom) as i32 + 1;
-
-    (from_x..to_x, from_y..to_y)
-  }
-
-  fn erase(&self) {
-    let canvas = self.canvas();
+    color: Rgb,
+  ) {
     let context = self.context();
-    context.set_fill_style(&JsValue::from_str("white"));
-    context.fill_rect(0.0, 0.0, canvas.width().into(), canvas.height().into())
-  }
-
-  fn size_to_cells(&self, settings: &Settings, size: f64, zoom: f64) -> f64 {
-    (size / (settings.cell_size * zoom + settings.grid_width) as f64).ceil()
+    let (pitch, grid_width) = (pitch(zoom, settings), grid_width(zoom, settings));
+    context.set_stroke_style(&JsValue::from(to_hex(color)));
+    context.set_line_width(2.0 * self.pixel_ratio);
+    context.stroke_rect(
+      offset.0 + min.x as f64 * pitch,
+      offset.1 + min.y as f64 * pitch,
+      (max.x - min.x + 1) as f64 * pitch + grid_width,
+      (max.y - min.y + 1) as f64 * pitch + grid_width,
+    );
   }
 
-  fn draw_grid(&self, settings: &Settings, offset: (f64, f64), zoom: f64) {
-    let canvas = self.canvas();
+  /// Outlines the object and writes its label above it.
+  fn draw_object(&self, settings: &Settings, object: &Object, offset: (f64, f64), zoom: f64) {
+    self.draw_selection(settings, object.bounds, offset, zoom, OBJECT_COLOR);
+    let label = match object.label() {
+      Some(label) => label,
+      None => return,
+    };
     let context = self.context();
-    context.set_fill_style(&JsValue::from_str(grey(0.9).as_str()));
-
-    let (cell_range_x, cell_range_y) = self.cell_range(settings, offset, zoom);
-    for i in cell_range_x {
-      context.fill_rect(
-        offset.0 + i as f64 * (zoom * settings.cell_size + settings.grid_width),
-        0.0,
-        settings.grid_width,
-        canvas.height().into(),
+    let pitch = pitch(zoom, settings);
+    let (min, _) = object.bounds;
+    context.set_fill_style(&JsValue::from(to_hex(OBJECT_COLOR)));
+    context.set_font(&format!("{}px sans-serif", 12.0 * self.pixel_ratio));
+    context
+      .fill_text(
+        label,
+        offset.0 + min.x as f64 * pitch,
+        offset.1 + min.y as f64 * pitch - 4.0 * self.pixel_ratio,
       )
-    }
-
-    for j in cell_range_y {
-      context.fill_rect(
-        0.0,
-        offset.1 + (j as f64 * (zoom * settings.cell_size + settings.grid_width)),
-        canvas.width().into(),
-        settings.grid_width,
-      )
-    }
+      .unwrap();
   }
 
-  fn draw_cells(
+  /// Outlines the cells of the parent generation, so that both generations
+  /// can be compared.
+  fn draw_parent(
     &self,
     settings: &Settings,
-    cells: &life::CellSet,
-    color: String,
+    parent: &life::CellSet,
     offset: (f64, f64),
     zoom: f64,
   ) {
     let context = self.context();
-    context.set_fill_style(&JsValue::from(color));
-
-    let (cell_range_x, cell_range_y) = self.cell_range(settings, offset, zoom);
-    let cells = cells.iter().filter(|Cell { x, y }| {
-      *x >= cell_range_x.start
-        && *x <= cell_range_x.end
-        && *y >= cell_range_y.start
-        && *y <= cell_range_y.end
-    });
-
-    for cell in cells {
-      context.fill_rect(
-        offset.0
-          + (settings.grid_width
-            + (zoom * settings.cell_size + settings.grid_width) * cell.x as f64),
-        offset.1
-          + (settings.grid_width
-            + (zoom * settings.cell_size + settings.grid_width) * cell.y as f64),
-        zoom * settings.cell_size,
-        zoom * settings.cell_size,
+    let (pitch, grid_width) = (pitch(zoom, settings), grid_width(zoom, settings));
+    context.set_stroke_style(&JsValue::from(to_hex(PARENT_COLOR)));
+    context.set_line_width(self.pixel_ratio);
+    for cell in parent {
+      context.stroke_rect(
+        offset.0 + cell.x as f64 * pitch + grid_width,
+        offset.1 + cell.y as f64 * pitch + grid_width,
+        pitch - grid_width,
+        pitch - grid_width,
       );
     }
   }
 
-  fn color_for_previous_gen(&self, gen_index: usize, num_gens: usize) -> String {
-    let from = 0.80_f64;
-    let to = 0.99_f64;
-    let coeff = gen_index as f64 * (to - from) / (num_gens as f64) + from;
-    crate::color_utils::grey(coeff)
+  /// Midpoint of the two pressed pointers and the distance between them.
+  fn pinch(&self) -> ((f64, f64), f64) {
+    let mut positions = self.pointers.values();
+    let (x1, y1) = *positions.next().unwrap();
+    let (x2, y2) = *positions.next().unwrap();
+    let (dx, dy) = ((x2 - x1) as f64, (y2 - y1) as f64);
+    (
+      ((x1 + x2) as f64 / 2.0, (y1 + y2) as f64 / 2.0),
+      (dx * dx + dy * dy).sqrt(),
+    )
+  }
+
+  fn reset_gesture(&mut self) {
+    self.last_offset = None;
+    self.selection_start = None;
+    self.pointer_down_at = None;
+    self.long_press = None;
+    self.drawing = None;
+  }
+
+  /// Size of the canvas in device pixels.
+  fn device_size(&self, ctx: &Context<Self>) -> (u32, u32) {
+    let scale = |size: u32| (size as f64 * self.pixel_ratio).round() as u32;
+    (scale(ctx.props().width), scale(ctx.props().height))
+  }
+
+  fn theme(&self, ctx: &Context<Self>) -> Theme {
+    ctx
+      .link()
+      .context::<Theme>(Callback::noop())
+      .expect("theme context to be set")
+      .0
   }
 
   fn settings(&self, ctx: &Context<Self>) -> Settings {
@@ -142,36 +220,161 @@ impl Board {
   }
 }
 
+/// Listens for the device pixel ratio to change from its current value.
+fn watch_pixel_ratio(ctx: &Context<Board>) -> Option<EventListener> {
+  let window = web_sys::window()?;
+  let query = format!("(resolution: {}dppx)", window.device_pixel_ratio());
+  let media_query_list = window.match_media(&query).ok()??;
+  let link = ctx.link().clone();
+  Some(EventListener::new(&media_query_list, "change", move |_| {
+    link.send_message(BoardMessage::ChangePixelRatio)
+  }))
+}
+
+fn within_click_tolerance((x1, y1): (i32, i32), (x2, y2): (i32, i32)) -> bool {
+  (x1 - x2).abs() <= CLICK_TOLERANCE && (y1 - y2).abs() <= CLICK_TOLERANCE
+}
+
 pub enum BoardMessage {
-  PointerDown(i32, i32),
-  PointerUp(i32, i32),
-  PointerMove(i32, i32),
+  /// Pointer id, position, whether Shift is pressed and whether it is a touch.
+  PointerDown(i32, i32, i32, bool, bool),
+  /// Pointer id, position and time stamp.
+  PointerUp(i32, i32, i32, f64),
+  PointerOut(i32),
+  PointerMove(i32, i32, i32),
   Zoom(i32, i32, f64),
+  LongPress,
+  ChangePixelRatio,
 }
 
 impl Component for Board {
   type Message = BoardMessage;
   type Properties = BoardProps;
 
-  fn create(_ctx: &Context<Self>) -> Self {
+  fn create(ctx: &Context<Self>) -> Self {
     Self {
       canvas_ref: NodeRef::default(),
+      image: RgbaImage::new(0, 0, BACKGROUND_COLOR),
+      pointers: HashMap::new(),
       last_offset: None,
+      selection_start: None,
+      pointer_down_at: None,
+      long_press: None,
+      drawing: None,
+      last_tap: None,
+      pixel_ratio: web_sys::window().unwrap().device_pixel_ratio(),
+      _pixel_ratio_handle: watch_pixel_ratio(ctx),
     }
   }
 
   fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
     match msg {
-      BoardMessage::PointerDown(x, y) => {
-        self.last_offset = Some((x as f64, y as f64));
+      BoardMessage::PointerDown(id, x, y, shift_key, touch) => {
+        self.pointers.insert(id, (x, y));
+        if self.pointers.len() > 1 {
+          // A second pointer turns the gesture into a pinch.
+          self.reset_gesture();
+        } else if shift_key {
+          let cell = self.cell_at(ctx, x, y);
+          self.selection_start = Some(cell);
+          ctx.props().select_region.emit(Some((cell, cell)));
+        } else {
+          self.last_offset = Some((x as f64, y as f64));
+          self.pointer_down_at = Some((x, y));
+          // A mouse or pen holding still mustn't start drawing.
+          if touch {
+            let link = ctx.link().clone();
+            self.long_press = Some(Timeout::new(LONG_PRESS_MILLIS, move || {
+              link.send_message(BoardMessage::LongPress)
+            }));
+          }
+        }
+        false
+      }
+      BoardMessage::PointerUp(id, x, y, time_stamp) => {
+        self.pointers.remove(&id);
+        if let Some(down_at) = self.pointer_down_at {
+          if within_click_tolerance((x, y), down_at) {
+            let cell = self.cell_at(ctx, x, y);
+            ctx.props().click_cell.emit(cell);
+            match self.last_tap {
+              Some((tap_x, tap_y, tap_time_stamp))
+                if time_stamp - tap_time_stamp < DOUBLE_TAP_MILLIS
+                  && within_click_tolerance((x, y), (tap_x, tap_y)) =>
+              {
+                self.last_tap = None;
+                let max_zoom = self.settings(ctx).max_zoom;
+                let zoom = (ctx.props().zoom * DOUBLE_TAP_ZOOM).clamp(MIN_ZOOM, max_zoom);
+                ctx.props().change_zoom.emit((x, y, zoom));
+              }
+              _ => self.last_tap = Some((x, y, time_stamp)),
+            }
+          }
+        }
+        self.reset_gesture();
+        // Lifting one finger of a pinch keeps panning with the other one.
+        if let Some(&(x, y)) = self.pointers.values().next() {
+          self.last_offset = Some((x as f64, y as f64));
+        }
         false
       }
-      BoardMessage::PointerUp(_x, _y) => {
-        self.last_offset = None;
+      BoardMessage::PointerOut(id) => {
+        if self.pointers.remove(&id).is_some() {
+          self.reset_gesture();
+        }
         false
       }
-      BoardMessage::PointerMove(x, y) => {
-        if let Some(last_offset) = self.last_offset {
+      BoardMessage::PointerMove(id, x, y) => {
+        if !self.pointers.contains_key(&id) {
+          return false;
+        }
+        if self.pointers.len() == 2 {
+          let (from, from_distance) = self.pinch();
+          self.pointers.insert(id, (x, y));
+          let (to, to_distance) = self.pinch();
+          let offset = ctx.props().offset;
+          ctx
+            .props()
+            .move_offset
+            .emit((offset.0 + to.0 - from.0, offset.1 + to.1 - from.1));
+          if from_distance > 0.0 {
+            let max_zoom = self.settings(ctx).max_zoom;
+            let zoom = (ctx.props().zoom * to_distance / from_distance).clamp(MIN_ZOOM, max_zoom);
+            ctx
+              .props()
+              .change_zoom
+              .emit((to.0 as i32, to.1 as i32, zoom));
+          }
+          return true;
+        }
+        self.pointers.insert(id, (x, y));
+        if let Some(down_at) = self.pointer_down_at {
+          if !within_click_tolerance((x, y), down_at) {
+            self.long_press = None;
+          }
+        }
+
+        if let Some((alive, last_cell)) = self.drawing {
+          let cell = self.cell_at(ctx, x, y);
+          if cell != last_cell {
+            ctx.props().draw_cell.emit((cell, alive));
+            self.drawing = Some((alive, cell));
+          }
+          false
+        } else if let Some(start) = self.selection_start {
+          let cell = self.cell_at(ctx, x, y);
+          ctx.props().select_region.emit(Some((
+            Cell {
+              x: start.x.min(cell.x),
+              y: start.y.min(cell.y),
+            },
+            Cell {
+              x: start.x.max(cell.x),
+              y: start.y.max(cell.y),
+            },
+          )));
+          false
+        } else if let Some(last_offset) = self.last_offset {
           let offset = ctx.props().offset;
           let new_offset = (
             offset.0 + x as f64 - last_offset.0,
@@ -189,57 +392,88 @@ impl Component for Board {
           false
         }
       }
+      BoardMessage::LongPress => {
+        self.long_press = None;
+        if let Some((x, y)) = self.pointer_down_at.take() {
+          let cell = self.cell_at(ctx, x, y);
+          let alive = !ctx.props().cells.contains(&cell);
+          ctx.props().draw_cell.emit((cell, alive));
+          self.last_offset = None;
+          self.drawing = Some((alive, cell));
+        }
+        false
+      }
       BoardMessage::Zoom(x1, y1, zoom) => {
-        let zoom = f64::max(f64::min(ctx.props().zoom - 0.1 * zoom / 120.0, 5.0), 0.1);
+        // Each notch of the wheel zooms by the same factor, so that zooming
+        // far out doesn't take forever.
+        let max_zoom = self.settings(ctx).max_zoom;
+        let zoom = (ctx.props().zoom * 0.9_f64.powf(zoom / 120.0)).clamp(MIN_ZOOM, max_zoom);
         ctx.props().change_zoom.emit((x1, y1, zoom));
         true
       }
+      BoardMessage::ChangePixelRatio => {
+        self.pixel_ratio = web_sys::window().unwrap().device_pixel_ratio();
+        // The query only matches the previous ratio.
+        self._pixel_ratio_handle = watch_pixel_ratio(ctx);
+        true
+      }
     }
   }
 
   fn rendered(&mut self, ctx: &Context<Self>, _first_render: bool) {
+    let (width, height) = self.device_size(ctx);
     let canvas = self.canvas();
-    canvas.set_width(ctx.props().width);
-    canvas.set_height(ctx.props().height);
-    let settings = self.settings(ctx);
+    canvas.set_width(width);
+    canvas.set_height(height);
+    let (offset, settings) = self.device_pixels(ctx);
+    let theme = self.theme(ctx);
     let zoom = ctx.props().zoom;
-    let offset = ctx.props().offset;
-    self.erase();
-    if ctx.props().zoom > 0.3 {
-      self.draw_grid(&settings, offset, zoom);
+    if (self.image.width, self.image.height) != (width, height) {
+      self.image = RgbaImage::new(width, height, BACKGROUND_COLOR);
     }
-    let previous_gens = &ctx.props().previous_gens;
-    let num_gens = previous_gens.len();
-    for i in 0..num_gens {
-      let gen_index = num_gens - i - 1;
-      self.draw_cells(
-        &settings,
-        &previous_gens[gen_index],
-        self.color_for_previous_gen(gen_index, num_gens),
-        offset,
-        zoom,
-      );
-    }
-    self.draw_cells(
-      &settings,
+    pixels::draw_board(
+      &mut self.image,
       &ctx.props().cells,
-      "#0d008b".to_string(),
+      &ctx.props().color_mode.layers(
+        &ctx.props().cells,
+        &ctx.props().previous_gens,
+        &settings,
+        &theme,
+      ),
       offset,
       zoom,
+      &settings,
+      &theme,
     );
+    canvas::put_image(&self.context(), &self.image);
+    for object in &ctx.props().objects {
+      self.draw_object(&settings, object, offset, zoom);
+    }
+    if let Some(parent) = &ctx.props().parent {
+      self.draw_parent(&settings, parent, offset, zoom);
+    }
+    for &region in &ctx.props().stop_regions {
+      self.draw_selection(&settings, region, offset, zoom, STOP_REGION_COLOR);
+    }
+    if let Some(selection) = ctx.props().selection {
+      self.draw_selection(&settings, selection, offset, zoom, theme.live_cell);
+    }
   }
 
   fn view(&self, ctx: &Context<Self>) -> Html {
+    let (width, height) = self.device_size(ctx);
     html! {
       <canvas
         ref={self.canvas_ref.clone()}
         class="board"
-        width={ctx.props().width.to_string()}
-        height={ctx.props().height.to_string()}
-        onpointerdown={ctx.link().callback(|event: PointerEvent| BoardMessage::PointerDown(event.client_x(), event.client_y()))}
-        onpointerup={ctx.link().callback(|event: PointerEvent| BoardMessage::PointerUp(event.client_x(), event.client_y()))}
-        onpointerout={ctx.link().callback(|event: PointerEvent| BoardMessage::PointerUp(event.client_x(), event.client_y()))}
-        onpointermove={ctx.link().callback(|event: PointerEvent| BoardMessage::PointerMove(event.client_x(), event.client_y()))}
+        width={width.to_string()}
+        height={height.to_string()}
+        style={format!("width: {}px; height: {}px", ctx.props().width, ctx.props().height)}
+        onpointerdown={ctx.link().callback(|event: PointerEvent| BoardMessage::PointerDown(event.pointer_id(), event.client_x(), event.client_y(), event.shift_key(), event.pointer_type() == "touch"))}
+        onpointerup={ctx.link().callback(|event: PointerEvent| BoardMessage::PointerUp(event.pointer_id(), event.client_x(), event.client_y(), event.time_stamp()))}
+        onpointerout={ctx.link().callback(|event: PointerEvent| BoardMessage::PointerOut(event.pointer_id()))}
+        onpointercancel={ctx.link().callback(|event: PointerEvent| BoardMessage::PointerOut(event.pointer_id()))}
+        onpointermove={ctx.link().callback(|event: PointerEvent| BoardMessage::PointerMove(event.pointer_id(), event.client_x(), event.client_y()))}
         onwheel={ctx.link().callback(|event: WheelEvent| BoardMessage::Zoom(event.client_x(), event.client_y(), event.delta_y()))}
       />
     }
@@ -0,0 +1,206 @@
+use crate::census::worker::{CensusWorker, Input, Progress, Start, CENSUS_WORKER_PATH};
+use crate::census::Census;
+use crate::download::download_file;
+use crate::soup::{SoupOptions, Symmetry};
+use gloo_worker::{Spawnable, WorkerBridge};
+use wasm_bindgen::JsCast;
+use web_sys::{HtmlInputElement, HtmlSelectElement};
+use yew::prelude::*;
+
+/// Objects listed in the panel; the exported census has all of them.
+const SHOWN_OBJECTS: usize = 20;
+const MAX_SOUPS: u64 = 1_000_000;
+
+/// Runs many soups in a worker and counts the objects they leave, like
+/// apgsearch, to be downloaded as CSV or JSON.
+pub struct CensusPanel {
+  worker: WorkerBridge<CensusWorker>,
+  options: SoupOptions,
+  prefix: String,
+  soups: u64,
+  census: Option<Census>,
+  running: bool,
+}
+
+pub enum Msg {
+  ChangeSymmetry(Symmetry),
+  ChangeSoups(u64),
+  ChangePrefix(String),
+  Start,
+  Stop,
+  ReceiveProgress(Progress),
+  DownloadCsv,
+  DownloadJson,
+}
+
+fn input_of(event: &Event) -> HtmlInputElement {
+  event
+    .target()
+    .and_then(|t| t.dyn_into::<HtmlInputElement>().ok())
+    .unwrap()
+}
+
+impl CensusPanel {
+  fn file_name(&self, extension: &str) -> String {
+    format!(
+      "census-{}-{}.{}",
+      self.options.symmetry.label(),
+      self.prefix,
+      extension
+    )
+  }
+
+  fn view_results(&self, ctx: &Context<Self>, census: &Census) -> Html {
+    html! {
+      <>
+        <div class="census-progress">
+          {format!("{} / {} soups", census.soups, self.soups)}
+        </div>
+        <table class="census-results">
+          <thead>
+            <tr><th>{"apgcode"}</th><th>{"Count"}</th></tr>
+          </thead>
+          <tbody>
+            {for census.sorted().into_iter().take(SHOWN_OBJECTS).map(|(apgcode, tally)| html! {
+              <tr>
+                <td class="apgcode" title={format!("First seen in soup {}", tally.soup)}>
+                  {apgcode}
+                </td>
+                <td>{tally.count}</td>
+              </tr>
+            })}
+          </tbody>
+        </table>
+        <button onclick={ctx.link().callback(|_| Msg::DownloadCsv)}>{"Download CSV"}</button>
+        <button onclick={ctx.link().callback(|_| Msg::DownloadJson)}>{"Download JSON"}</button>
+      </>
+    }
+  }
+}
+
+impl Component for CensusPanel {
+  type Message = Msg;
+  type Properties = ();
+
+  fn create(ctx: &Context<Self>) -> Self {
+    let link = ctx.link().clone();
+    let worker = CensusWorker::spawner()
+      .callback(move |progress| link.send_message(Msg::ReceiveProgress(progress)))
+      .spawn(CENSUS_WORKER_PATH);
+    Self {
+      worker,
+      options: SoupOptions::default(),
+      prefix: "census_".to_string(),
+      soups: 1000,
+      census: None,
+      running: false,
+    }
+  }
+
+  fn update(&mut self, _: &Context<Self>, msg: Self::Message) -> bool {
+    match msg {
+      Msg::ChangeSymmetry(symmetry) => self.options.symmetry = symmetry,
+      Msg::ChangeSoups(soups) => self.soups = soups.clamp(1, MAX_SOUPS),
+      Msg::ChangePrefix(prefix) => self.prefix = prefix,
+      Msg::Start => {
+        self.worker.send(Input::Start(Start {
+          prefix: self.prefix.clone(),
+          soups: self.soups,
+          options: self.options.clone(),
+        }));
+        self.census = None;
+        self.running = true;
+      }
+      Msg::Stop => {
+        self.worker.send(Input::Stop);
+        self.running = false;
+      }
+      Msg::ReceiveProgress(progress) => {
+        // Batches finished before the worker received `Stop` are still shown.
+        self.running &= !progress.done;
+        self.census = Some(progress.census);
+      }
+      Msg::DownloadCsv => {
+        if let Some(census) = &self.census {
+          download_file(
+            census.to_csv().as_bytes(),
+            "text/csv",
+            &self.file_name("csv"),
+          );
+        }
+      }
+      Msg::DownloadJson => {
+        if let Some(census) = &self.census {
+          download_file(
+            census.to_json().as_bytes(),
+            "application/json",
+            &self.file_name("json"),
+          );
+        }
+      }
+    }
+    true
+  }
+
+  fn view(&self, ctx: &Context<Self>) -> Html {
+    let on_change_symmetry = ctx.link().callback(|event: Event| {
+      let select = event
+        .target()
+        .and_then(|t| t.dyn_into::<HtmlSelectElement>().ok())
+        .unwrap();
+      let index: usize = select.value().parse().unwrap();
+      Msg::ChangeSymmetry(Symmetry::ALL[index])
+    });
+    let on_change_soups = ctx
+      .link()
+      .batch_callback(|event: Event| input_of(&event).value().parse().ok().map(Msg::ChangeSoups));
+    let on_change_prefix = ctx
+      .link()
+      .callback(|event: Event| Msg::ChangePrefix(input_of(&event).value()));
+
+    html! {
+      <details class="census-panel">
+        <summary>{"Census"}</summary>
+        <div class="census-options">
+          <label>
+            <span>{"Soups"}</span>
+            <input
+              type="number" min="1" max={MAX_SOUPS.to_string()}
+              value={self.soups.to_string()}
+              disabled={self.running}
+              onchange={on_change_soups}
+            />
+          </label>
+          <label>
+            <span>{"Symmetry"}</span>
+            <select onchange={on_change_symmetry} disabled={self.running}>
+              {for Symmetry::ALL.iter().enumerate().map(|(i, &symmetry)| html! {
+                <option value={i.to_string()} selected={symmetry == self.options.symmetry}>
+                  {symmetry.label()}
+                </option>
+              })}
+            </select>
+          </label>
+          <label>
+            <span>{"Seed prefix"}</span>
+            <input
+              type="text"
+              value={self.prefix.clone()}
+              disabled={self.running}
+              onchange={on_change_prefix}
+            />
+          </label>
+        </div>
+        {if self.running {
+          html! { <button onclick={ctx.link().callback(|_| Msg::Stop)}>{"Stop"}</button> }
+        } else {
+          html! { <button onclick={ctx.link().callback(|_| Msg::Start)}>{"Start"}</button> }
+        }}
+        {match &self.census {
+          Some(census) => self.view_results(ctx, census),
+          None => html! {},
+        }}
+      </details>
+    }
+  }
+}
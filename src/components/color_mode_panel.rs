@@ -0,0 +1,79 @@
+use crate::color_utils::to_hex;
+use crate::render::color_mode::ColorMode;
+use crate::settings::Settings;
+use crate::theme::Theme;
+use wasm_bindgen::JsCast;
+use web_sys::HtmlSelectElement;
+use yew::prelude::*;
+
+/// Picks how cells are colored, and explains the colors of the current mode.
+pub struct ColorModePanel;
+
+#[derive(Properties, PartialEq)]
+pub struct Props {
+  pub color_mode: ColorMode,
+  pub on_change: Callback<ColorMode>,
+}
+
+pub enum Msg {
+  Change(ColorMode),
+}
+
+impl Component for ColorModePanel {
+  type Message = Msg;
+  type Properties = Props;
+
+  fn create(_: &Context<Self>) -> Self {
+    Self
+  }
+
+  fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+    match msg {
+      Msg::Change(color_mode) => ctx.props().on_change.emit(color_mode),
+    }
+    false
+  }
+
+  fn view(&self, ctx: &Context<Self>) -> Html {
+    let on_change = ctx.link().callback(|event: Event| {
+      let select = event
+        .target()
+        .and_then(|t| t.dyn_into::<HtmlSelectElement>().ok())
+        .unwrap();
+      let index: usize = select.value().parse().unwrap();
+      Msg::Change(ColorMode::ALL[index])
+    });
+    let color_mode = ctx.props().color_mode;
+    let theme = ctx
+      .link()
+      .context::<Theme>(Callback::noop())
+      .expect("theme context to be set")
+      .0;
+    let settings = ctx
+      .link()
+      .context::<Settings>(Callback::noop())
+      .expect("settings context to be set")
+      .0;
+
+    html! {
+      <div class="color-mode">
+        <div class="color-mode-select">
+          <span>{"Colors"}</span>
+          <select onchange={on_change}>
+            {for ColorMode::ALL.iter().enumerate().map(|(i, &mode)| html! {
+              <option value={i.to_string()} selected={mode == color_mode}>{mode.label()}</option>
+            })}
+          </select>
+        </div>
+        <ul class="legend">
+          {for color_mode.legend(&settings, &theme).into_iter().map(|(color, label)| html! {
+            <li>
+              <span class="swatch" style={format!("background-color: {}", to_hex(color))} />
+              {label}
+            </li>
+          })}
+        </ul>
+      </div>
+    }
+  }
+}
@@ -0,0 +1,195 @@
+use crate::keybindings::{Action, KeyBindings};
+use crate::lexicon::*;
+use crate::search::fuzzy_score;
+use wasm_bindgen::JsCast;
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+const MAX_RESULTS: usize = 50;
+
+/// Dialog searching actions and patterns of the lexicon by name, opened with
+/// Ctrl+K.
+pub struct CommandPalette {
+  input_ref: NodeRef,
+  terms: &'static [Term],
+  query: String,
+  results: Vec<Entry>,
+  selected: usize,
+}
+
+#[derive(Clone, Copy)]
+enum Entry {
+  Action(Action),
+  /// Index of a term of the lexicon.
+  Pattern(usize),
+}
+
+#[derive(Properties, PartialEq)]
+pub struct Props {
+  pub key_bindings: KeyBindings,
+  pub on_action: Callback<Action>,
+  pub on_apply_pattern: Callback<Term>,
+  pub on_close: Callback<()>,
+}
+
+pub enum Msg {
+  ChangeQuery(String),
+  KeyDown(KeyboardEvent),
+  Select(usize),
+  Run(usize),
+  Close,
+}
+
+impl CommandPalette {
+  /// Actions and patterns matching the query, best matches first. Patterns
+  /// only show up once something is typed, as there are too many of them.
+  fn search(&self) -> Vec<Entry> {
+    let actions = Action::ALL
+      .iter()
+      .filter(|&&action| action != Action::CommandPalette)
+      .filter_map(|&action| {
+        fuzzy_score(&self.query, action.label()).map(|score| (score, Entry::Action(action)))
+      });
+    let patterns = self
+      .terms
+      .iter()
+      .enumerate()
+      .filter(|_| !self.query.trim().is_empty())
+      .filter_map(|(i, term)| {
+        fuzzy_score(&self.query, &term.name).map(|score| (score, Entry::Pattern(i)))
+      });
+    let mut results: Vec<(i32, Entry)> = actions.chain(patterns).collect();
+    results.sort_by_key(|&(score, _)| -score);
+    results
+      .into_iter()
+      .take(MAX_RESULTS)
+      .map(|(_, entry)| entry)
+      .collect()
+  }
+
+  fn run(&self, ctx: &Context<Self>, entry: Entry) {
+    ctx.props().on_close.emit(());
+    match entry {
+      Entry::Action(action) => ctx.props().on_action.emit(action),
+      Entry::Pattern(i) => ctx.props().on_apply_pattern.emit(self.terms[i].clone()),
+    }
+  }
+}
+
+impl Component for CommandPalette {
+  type Message = Msg;
+  type Properties = Props;
+
+  fn create(_: &Context<Self>) -> Self {
+    let mut palette = Self {
+      input_ref: NodeRef::default(),
+      terms: &Lexicon::get().terms,
+      query: String::new(),
+      results: vec![],
+      selected: 0,
+    };
+    palette.results = palette.search();
+    palette
+  }
+
+  fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+    match msg {
+      Msg::ChangeQuery(query) => {
+        self.query = query;
+        self.results = self.search();
+        self.selected = 0;
+        true
+      }
+      Msg::KeyDown(event) => match event.key().as_str() {
+        "ArrowDown" | "ArrowUp" if !self.results.is_empty() => {
+          event.prevent_default();
+          let len = self.results.len();
+          self.selected = if event.key() == "ArrowDown" {
+            (self.selected + 1) % len
+          } else {
+            (self.selected + len - 1) % len
+          };
+          true
+        }
+        "Enter" => {
+          if let Some(&entry) = self.results.get(self.selected) {
+            self.run(ctx, entry);
+          }
+          false
+        }
+        "Escape" => {
+          ctx.props().on_close.emit(());
+          false
+        }
+        _ => false,
+      },
+      Msg::Select(i) => {
+        self.selected = i;
+        true
+      }
+      Msg::Run(i) => {
+        self.run(ctx, self.results[i]);
+        false
+      }
+      Msg::Close => {
+        ctx.props().on_close.emit(());
+        false
+      }
+    }
+  }
+
+  fn rendered(&mut self, _: &Context<Self>, first_render: bool) {
+    if first_render {
+      if let Some(input) = self.input_ref.cast::<HtmlInputElement>() {
+        input.focus().ok();
+      }
+    }
+  }
+
+  fn view(&self, ctx: &Context<Self>) -> Html {
+    let on_input = ctx.link().callback(|event: InputEvent| {
+      let input = event
+        .target()
+        .and_then(|t| t.dyn_into::<HtmlInputElement>().ok())
+        .unwrap();
+      Msg::ChangeQuery(input.value())
+    });
+
+    html! {
+      <>
+        <div class="command-palette-backdrop" onclick={ctx.link().callback(|_| Msg::Close)} />
+        <div class="command-palette">
+          <input
+            ref={self.input_ref.clone()}
+            type="search"
+            placeholder="Search actions and patterns…"
+            value={self.query.clone()}
+            oninput={on_input}
+            onkeydown={ctx.link().callback(Msg::KeyDown)}
+          />
+          <ul class="results">
+            {for self.results.iter().enumerate().map(|(i, &entry)| {
+              let (label, hint) = match entry {
+                Entry::Action(action) => (
+                  action.label().to_string(),
+                  ctx.props().key_bindings.keys_for(action).join(", "),
+                ),
+                Entry::Pattern(term) => (self.terms[term].name.clone(), "Pattern".to_string()),
+              };
+              html! {
+                <li
+                  class={classes!((i == self.selected).then_some("selected"))}
+                  onmouseover={ctx.link().callback(move |_| Msg::Select(i))}
+                  onclick={ctx.link().callback(move |_| Msg::Run(i))}
+                >
+                  <span>{label}</span>
+                  <span class="shortcut">{hint}</span>
+                </li>
+              }
+            })}
+          </ul>
+        </div>
+      </>
+    }
+  }
+}
@@ -0,0 +1,181 @@
+use crate::download::download_file;
+use crate::export::worker::{ExportWorker, Input, EXPORT_WORKER_PATH};
+use crate::export::*;
+use crate::life::CellSet;
+use crate::settings::Settings;
+use crate::theme::Theme;
+use crate::viewport::visible_area;
+use gloo_worker::{Spawnable, WorkerBridge};
+use wasm_bindgen::JsCast;
+use web_sys::{HtmlInputElement, HtmlSelectElement};
+use yew::prelude::*;
+
+pub struct ExportPanel {
+  generations: u32,
+  svg_viewport: bool,
+  /// Renders GIFs off the main thread.
+  worker: WorkerBridge<ExportWorker>,
+  /// Whether a GIF is being rendered by the worker.
+  rendering_gif: bool,
+}
+
+#[derive(Properties, PartialEq)]
+pub struct Props {
+  pub cells: CellSet,
+  pub previous_gens: Vec<CellSet>,
+  pub offset: (f64, f64),
+  pub zoom: f64,
+  pub width: u32,
+  pub height: u32,
+}
+
+pub enum Msg {
+  ChangeGenerations(u32),
+  ChangeSvgScope(bool),
+  ExportPng,
+  ExportGif,
+  ReceiveGif(Result<Vec<u8>, String>),
+  ExportSvg,
+}
+
+impl ExportPanel {
+  fn theme(&self, ctx: &Context<Self>) -> Theme {
+    ctx
+      .link()
+      .context::<Theme>(Callback::noop())
+      .expect("theme context to be set")
+      .0
+  }
+
+  fn settings(&self, ctx: &Context<Self>) -> Settings {
+    ctx
+      .link()
+      .context::<Settings>(Callback::noop())
+      .expect("settings context to be set")
+      .0
+  }
+
+  /// Area of the board currently displayed, converted from canvas pixels to
+  /// cells the same way `Board` positions them.
+  fn viewport(&self, ctx: &Context<Self>) -> Area {
+    let props = ctx.props();
+    visible_area(
+      props.offset,
+      props.zoom,
+      (props.width, props.height),
+      &self.settings(ctx),
+    )
+  }
+}
+
+impl Component for ExportPanel {
+  type Message = Msg;
+  type Properties = Props;
+
+  fn create(ctx: &Context<Self>) -> Self {
+    let link = ctx.link().clone();
+    let worker = ExportWorker::spawner()
+      .callback(move |gif| link.send_message(Msg::ReceiveGif(gif)))
+      .spawn(EXPORT_WORKER_PATH);
+    Self {
+      generations: 50,
+      svg_viewport: false,
+      worker,
+      rendering_gif: false,
+    }
+  }
+
+  fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+    let options = ExportOptions {
+      theme: self.theme(ctx),
+      ..ExportOptions::default()
+    };
+    match msg {
+      Msg::ChangeGenerations(generations) => {
+        self.generations = generations;
+        true
+      }
+      Msg::ChangeSvgScope(svg_viewport) => {
+        self.svg_viewport = svg_viewport;
+        true
+      }
+      Msg::ExportPng => {
+        let trail = self.settings(ctx).trail(&ctx.props().previous_gens);
+        match render_png(&ctx.props().cells, trail, &options) {
+          Ok(bytes) => download_file(&bytes, "image/png", "lifeee.png"),
+          Err(error) => gloo::dialogs::alert(&error.to_string()),
+        }
+        false
+      }
+      Msg::ExportGif => {
+        self.worker.send(Input {
+          cells: ctx.props().cells.clone(),
+          generations: self.generations,
+          trail_length: self.settings(ctx).trail_length,
+          options,
+        });
+        self.rendering_gif = true;
+        true
+      }
+      Msg::ReceiveGif(gif) => {
+        match gif {
+          Ok(bytes) => download_file(&bytes, "image/gif", "lifeee.gif"),
+          Err(error) => gloo::dialogs::alert(&error),
+        }
+        self.rendering_gif = false;
+        true
+      }
+      Msg::ExportSvg => {
+        let scope = if self.svg_viewport {
+          SvgScope::Viewport(self.viewport(ctx), ctx.props().zoom)
+        } else {
+          SvgScope::BoundingBox
+        };
+        let settings = self.settings(ctx);
+        let trail = settings.trail(&ctx.props().previous_gens);
+        let svg = render_svg(&ctx.props().cells, trail, scope, &options, &settings);
+        download_file(svg.as_bytes(), "image/svg+xml", "lifeee.svg");
+        false
+      }
+    }
+  }
+
+  fn view(&self, ctx: &Context<Self>) -> Html {
+    let on_change_generations = ctx.link().callback(|event: Event| {
+      let input = event
+        .target()
+        .and_then(|t| t.dyn_into::<HtmlInputElement>().ok())
+        .unwrap();
+      Msg::ChangeGenerations(input.value().parse().unwrap_or(50))
+    });
+
+    let on_change_svg_scope = ctx.link().callback(|event: Event| {
+      let select = event
+        .target()
+        .and_then(|t| t.dyn_into::<HtmlSelectElement>().ok())
+        .unwrap();
+      Msg::ChangeSvgScope(select.value() == "viewport")
+    });
+
+    html! {
+      <div class="export-panel">
+        <span>{"Export"}</span>
+        <button onclick={ctx.link().callback(|_| Msg::ExportPng)}>{"PNG"}</button>
+        <button disabled={self.rendering_gif} onclick={ctx.link().callback(|_| Msg::ExportGif)}>
+          {if self.rendering_gif { "Rendering…" } else { "GIF" }}
+        </button>
+        <input
+          type="number" min="1" max="1000"
+          value={self.generations.to_string()}
+          onchange={on_change_generations}
+        />
+        <span class="unit">{"generations"}</span>
+        <button onclick={ctx.link().callback(|_| Msg::ExportSvg)}>{"SVG"}</button>
+        <select onchange={on_change_svg_scope}>
+          <option value="pattern" selected={!self.svg_viewport}>{"Pattern"}</option>
+          <option value="viewport" selected={self.svg_viewport}>{"View"}</option>
+        </select>
+      </div>
+    }
+  }
+}
@@ -1,41 +1,172 @@
+use crate::components::apgcode_input::ApgcodeInput;
 use crate::components::board::Board;
+use crate::components::census_panel::CensusPanel;
+use crate::components::color_mode_panel::ColorModePanel;
+use crate::components::command_palette::CommandPalette;
+use crate::components::export_panel::ExportPanel;
+use crate::components::key_bindings_panel::KeyBindingsPanel;
+use crate::components::library_panel::LibraryPanel;
+use crate::components::minimap::Minimap;
 use crate::components::pattern_selector::PatternSelector;
+use crate::components::predecessor_panel::PredecessorPanel;
+use crate::components::settings_dialog::SettingsDialog;
+use crate::components::soup_panel::SoupPanel;
+use crate::components::stop_panel::StopPanel;
+use crate::components::theme_panel::ThemePanel;
+use crate::engine::{Engine, Generation, Input, WORKER_PATH};
+use crate::keybindings::{is_modifier, key_name, Action, KeyBindings};
+use crate::lexicon::{Cell, Lexicon, Term};
+use crate::library::UserLibrary;
+use crate::life::analysis::{Classifier, Object};
+use crate::life::objects::{centroid, object_at, track_object};
 use crate::life::*;
+use crate::render::color_mode::ColorMode;
+use crate::speed::{RateMeter, Speed};
+use crate::stop::{StopConditions, StopReason};
+use crate::theme::{Theme, ThemeChoice};
+use crate::viewport::{center_of, offset_centered_on, pitch, zoom_to_fit, MIN_ZOOM};
 use crate::Settings;
-use gloo::events::EventListener;
-use gloo::timers::callback::Interval;
-use lexicon::Term;
+use gloo::events::{EventListener, EventListenerOptions};
+use gloo::render::{request_animation_frame, AnimationFrame};
+use gloo_worker::{Spawnable, WorkerBridge};
 use std::collections::VecDeque;
 use wasm_bindgen::JsCast;
-use web_sys::HtmlInputElement;
+use web_sys::{HtmlInputElement, HtmlSelectElement};
 use yew::prelude::*;
 
+/// Fraction of the distance to the followed pattern the view moves at each
+/// tick, so that it glides instead of jumping.
+const FOLLOW_SMOOTHING: f64 = 0.3;
+/// Cells kept around the pattern when zooming to fit it.
+const FIT_MARGIN: i32 = 2;
+/// Pixels the view moves by when panning with the keyboard.
+const PAN_STEP: f64 = 50.0;
+/// Factor the zoom is multiplied or divided by when zooming with the keyboard.
+const ZOOM_STEP: f64 = 1.25;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum FollowMode {
+  Off,
+  Centroid,
+  BoundingBox,
+}
+
 pub struct Game {
   cells: CellSet,
   previous_gens: Vec<CellSet>,
   tick: u32,
-  interval: Option<Interval>,
-  speed: u8,
+  running: bool,
+  speed: Speed,
+  /// Simulation running in a Web Worker.
+  engine: WorkerBridge<Engine>,
+  /// Incremented when the cells are changed on the page and loaded into the
+  /// engine, to ignore the generations it computed from the previous ones.
+  epoch: u32,
+  /// Newest generation received from the engine, shown at the next frame.
+  pending_generation: Option<Generation>,
+  next_frame: Option<AnimationFrame>,
+  /// Generations due at the next frames with a step exponent below 0, as a
+  /// fraction of a generation is due at each frame.
+  frame_gens: f64,
+  /// Whether the engine is computing the generations asked for at a frame.
+  advancing: bool,
+  /// Whether a generation received from the engine is yet to be rendered.
+  awaiting_render: bool,
+  /// Frames showing a new generation since the simulation started playing.
+  frames: u32,
+  gens_meter: RateMeter,
+  frames_meter: RateMeter,
+  stop_conditions: StopConditions,
+  /// Why the simulation stopped on its own, until dismissed or played again.
+  stop_notice: Option<StopReason>,
   adjust_offset: Option<(usize, usize)>,
   offset: (f64, f64),
   zoom: f64,
   width: u32,
   height: u32,
+  selection: Option<(Cell, Cell)>,
+  library: UserLibrary,
+  follow: FollowMode,
+  follow_zoom: bool,
+  followed_object: Option<CellSet>,
+  /// Whether the followed object could not be found in a new generation,
+  /// until dismissed or another object is picked.
+  lost_object: bool,
+  picking_object: bool,
+  classifier: Classifier,
+  /// Objects of the board, while they are labelled.
+  objects: Option<Vec<Object>>,
+  /// Name of the pattern of the lexicon the board is, if any.
+  identified: Option<String>,
+  /// Parent of the board found by the predecessor search, shown over it.
+  parent: Option<CellSet>,
+  color_mode: ColorMode,
+  palette_open: bool,
+  settings_open: bool,
+  /// Action waiting for the next key pressed to be bound to it.
+  capturing_key: Option<Action>,
   _resize_handle: EventListener,
+  _keydown_handle: EventListener,
+}
+
+#[derive(Properties, PartialEq)]
+pub struct Props {
+  pub on_change_settings: Callback<Settings>,
 }
 
 pub enum Msg {
   NextTick,
+  ReceiveGeneration(Generation),
+  /// Time of the animation frame, in milliseconds.
+  Frame(f64),
+  ChangeStopConditions(StopConditions),
+  DismissStopNotice,
+  DismissLostObject,
   Play,
   Pause,
-  ChangeSpeed(u8),
+  ChangeSpeed(Speed),
   ApplyPattern(Term),
   MoveOffset((f64, f64)),
   ChangeZoom((i32, i32, f64)),
   Resize,
+  SelectRegion(Option<(Cell, Cell)>),
+  ChangeLibrary(UserLibrary),
+  ChangeFollow(FollowMode),
+  ChangeFollowZoom(bool),
+  PickObject,
+  ToggleObjectLabels,
+  ShowParent(Option<CellSet>),
+  ChangeColorMode(ColorMode),
+  ClickCell(Cell),
+  DrawCell((Cell, bool)),
+  FitAll,
+  ResetView,
+  KeyDown(KeyboardEvent),
+  RunAction(Action),
+  ClosePalette,
+  CloseSettings,
+  CaptureKey(Option<Action>),
+  ChangeKeyBindings(KeyBindings),
+  ChangeTheme(ThemeChoice),
+}
+
+/// Whether a key is typed in a form field, rather than meant as a shortcut.
+fn is_typing(event: &KeyboardEvent) -> bool {
+  event
+    .target()
+    .and_then(|target| target.dyn_into::<web_sys::Element>().ok())
+    .is_some_and(|element| matches!(element.tag_name().as_str(), "INPUT" | "TEXTAREA" | "SELECT"))
 }
 
 impl Game {
+  fn theme(&self, ctx: &Context<Self>) -> Theme {
+    ctx
+      .link()
+      .context::<Theme>(Callback::noop())
+      .expect("theme context to be set")
+      .0
+  }
+
   fn settings(&self, ctx: &Context<Self>) -> Settings {
     ctx
       .link()
@@ -44,58 +175,233 @@ impl Game {
       .0
   }
 
-  fn start_interval(&mut self, ctx: &Context<Self>) {
-    let link = ctx.link().clone();
-    link.send_message(Msg::NextTick);
-    let millis = (50_f64 - 500_f64) / 9_f64 * self.speed as f64 + 500_f64;
-    let interval = Interval::new(millis as u32, move || link.send_message(Msg::NextTick));
-    self.interval = Some(interval);
+  /// Moves the view (and zooms it if asked to) towards the followed pattern:
+  /// the picked object if any, or else the whole population.
+  fn follow(&mut self, settings: &Settings) {
+    let target = self.followed_object.as_ref().unwrap_or(&self.cells);
+    let center = match self.follow {
+      FollowMode::Off => None,
+      FollowMode::Centroid => centroid(target).map(|(x, y)| (x + 0.5, y + 0.5)),
+      FollowMode::BoundingBox => bounding_box(target).map(center_of),
+    };
+    let center = match center {
+      Some(center) => center,
+      None => return,
+    };
+    let size = (self.width, self.height);
+
+    if self.follow_zoom {
+      if let Some(bounds) = bounding_box(target) {
+        let zoom = zoom_to_fit(bounds, FIT_MARGIN, size, settings);
+        self.zoom += (zoom - self.zoom) * FOLLOW_SMOOTHING;
+      }
+    }
+
+    let target_offset = offset_centered_on(center, self.zoom, size, settings);
+    self.offset = (
+      self.offset.0 + (target_offset.0 - self.offset.0) * FOLLOW_SMOOTHING,
+      self.offset.1 + (target_offset.1 - self.offset.1) * FOLLOW_SMOOTHING,
+    );
+  }
+
+  /// Message zooming around the center of the board.
+  fn zoom_message(&self, zoom: f64, settings: &Settings) -> Msg {
+    Msg::ChangeZoom((
+      (self.width / 2) as i32,
+      (self.height / 2) as i32,
+      zoom.clamp(MIN_ZOOM, settings.max_zoom),
+    ))
+  }
+
+  fn play(&mut self, ctx: &Context<Self>) {
+    if !self.running {
+      self.engine.send(Input::Step);
+    }
+    self.engine.send(Input::Play { speed: self.speed });
+    self.running = true;
+    self.frame_gens = 0.0;
+    self.advancing = false;
+    self.request_frame(ctx);
+    self.stop_notice = None;
+    self.frames = 0;
+    self.gens_meter.clear();
+    self.frames_meter.clear();
+  }
+
+  /// Asks for `Msg::Frame` at the next animation frame, unless already asked.
+  fn request_frame(&mut self, ctx: &Context<Self>) {
+    if self.next_frame.is_none() {
+      let link = ctx.link().clone();
+      self.next_frame = Some(request_animation_frame(move |time| {
+        link.send_message(Msg::Frame(time))
+      }));
+    }
+  }
+
+  /// Asks the engine for the generations of a frame with a step exponent,
+  /// once it sent those asked for at a previous frame, so that it never falls
+  /// behind.
+  fn advance(&mut self, gens_per_frame: f64) {
+    if self.advancing {
+      return;
+    }
+    self.frame_gens += gens_per_frame;
+    let gens = self.frame_gens.floor();
+    if gens >= 1.0 {
+      self.engine.send(Input::Advance { gens: gens as u32 });
+      self.frame_gens -= gens;
+      self.advancing = true;
+    }
+  }
+
+  /// Shows a generation computed by the engine, keeping the previous one in
+  /// the history.
+  fn show_generation(&mut self, generation: Generation, settings: &Settings) {
+    let gens = generation.tick.saturating_sub(self.tick);
+    self.tick = generation.tick;
+    self.adjust_offset = None;
+    self.awaiting_render = true;
+
+    self.previous_gens = {
+      let mut previous_gens_deque: VecDeque<CellSet> = self
+        .previous_gens
+        .iter()
+        .map(|cell_set| cell_set.clone())
+        .collect();
+      previous_gens_deque.push_front(self.cells.clone());
+      previous_gens_deque.truncate(settings.num_previous);
+      previous_gens_deque
+        .iter()
+        .map(|cell_set| cell_set.clone())
+        .collect()
+    };
+
+    self.cells = generation.cells;
+
+    if let Some(object) = &self.followed_object {
+      let object = track_object(object, &self.cells, settings.object_distance, gens);
+      if object.is_empty() {
+        // Stop there rather than jump to the whole population.
+        self.followed_object = None;
+        self.follow = FollowMode::Off;
+        self.lost_object = true;
+      } else {
+        self.followed_object = Some(object);
+      }
+    }
+    self.follow(settings);
+    self.analyze_board(settings);
+  }
+
+  /// Identifies the board in the lexicon, and splits it into objects again if
+  /// they are labelled. The board is only identified once it stops changing
+  /// at each frame, as running it for a whole period takes time.
+  fn analyze_board(&mut self, settings: &Settings) {
+    self.identified = if self.running {
+      None
+    } else {
+      self.classifier.identify(&self.cells)
+    };
+    if self.objects.is_some() {
+      self.objects = Some(
+        self
+          .classifier
+          .analyze(&self.cells, settings.object_distance),
+      );
+    }
+  }
+
+  /// Loads the cells into the engine, after they were changed on the page.
+  fn load_cells(&mut self) {
+    self.epoch += 1;
+    self.engine.send(Input::Load {
+      cells: self.cells.clone(),
+      tick: self.tick,
+      epoch: self.epoch,
+    });
+    if self.pending_generation.take().is_some() {
+      self.engine.send(Input::Shown);
+    }
+    self.advancing = false;
   }
 }
 
 impl Component for Game {
   type Message = Msg;
-  type Properties = ();
+  type Properties = Props;
 
   fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
     let settings = self.settings(ctx);
     match msg {
       Msg::NextTick => {
-        self.tick += 1;
-        self.adjust_offset = None;
-
-        self.previous_gens = {
-          let mut previous_gens_deque: VecDeque<CellSet> = self
-            .previous_gens
-            .iter()
-            .map(|cell_set| cell_set.clone())
-            .collect();
-          previous_gens_deque.push_front(self.cells.clone());
-          if previous_gens_deque.len() > settings.num_previous {
-            previous_gens_deque.pop_back();
+        self.engine.send(Input::Step);
+        false
+      }
+      Msg::ReceiveGeneration(generation) => {
+        self.advancing = false;
+        let stopped = generation.stopped.is_some();
+        if let Some(reason) = &generation.stopped {
+          self.running = false;
+          self.stop_notice = Some(reason.clone());
+        }
+        if generation.epoch != self.epoch {
+          self.engine.send(Input::Shown);
+          return stopped;
+        }
+        self.pending_generation = Some(generation);
+        self.request_frame(ctx);
+        stopped
+      }
+      Msg::Frame(time) => {
+        self.next_frame = None;
+        let shown = match self.pending_generation.take() {
+          Some(generation) => {
+            self.show_generation(generation, &settings);
+            if self.running {
+              self.frames += 1;
+              self.frames_meter.record(time, self.frames as f64);
+              self.gens_meter.record(time, self.tick as f64);
+            }
+            true
           }
-          previous_gens_deque
-            .iter()
-            .map(|cell_set| cell_set.clone())
-            .collect()
+          None => false,
         };
-
-        self.cells = tick(&self.cells);
-
+        // With a step exponent, generations are asked for at every frame.
+        if let Some(gens_per_frame) = self.speed.gens_per_frame().filter(|_| self.running) {
+          self.advance(gens_per_frame);
+          self.request_frame(ctx);
+        }
+        shown
+      }
+      Msg::ChangeStopConditions(conditions) => {
+        self
+          .engine
+          .send(Input::SetStopConditions(conditions.clone()));
+        self.stop_conditions = conditions;
+        true
+      }
+      Msg::DismissStopNotice => {
+        self.stop_notice = None;
+        true
+      }
+      Msg::DismissLostObject => {
+        self.lost_object = false;
         true
       }
       Msg::Play => {
-        self.start_interval(ctx);
+        self.play(ctx);
         true
       }
       Msg::Pause => {
-        self.interval = None;
+        self.engine.send(Input::Pause);
+        self.running = false;
+        self.analyze_board(&settings);
         true
       }
       Msg::ChangeSpeed(speed) => {
         self.speed = speed;
-        if self.interval.is_some() {
-          self.start_interval(ctx);
+        if self.running {
+          self.play(ctx);
         }
         true
       }
@@ -106,6 +412,10 @@ impl Component for Game {
           .fold(CellSet::new(), |cells, &cell| make_cell_alive(&cells, cell));
         self.tick = 0;
         self.previous_gens = vec![];
+        self.load_cells();
+        self.selection = None;
+        self.followed_object = None;
+        self.analyze_board(&settings);
         self.offset = (
           (self.width as f64 / 2_f64
             - term.width as f64 * self.zoom * (settings.cell_size + settings.grid_width) as f64
@@ -140,6 +450,194 @@ impl Component for Game {
         );
         true
       }
+      Msg::SelectRegion(selection) => {
+        self.selection = selection;
+        true
+      }
+      Msg::ChangeLibrary(library) => {
+        library.save();
+        self.library = library;
+        true
+      }
+      Msg::ChangeFollow(follow) => {
+        self.follow = follow;
+        if follow == FollowMode::Off {
+          self.followed_object = None;
+        }
+        self.follow(&settings);
+        true
+      }
+      Msg::ChangeFollowZoom(follow_zoom) => {
+        self.follow_zoom = follow_zoom;
+        self.follow(&settings);
+        true
+      }
+      Msg::PickObject => {
+        self.picking_object = !self.picking_object;
+        true
+      }
+      Msg::ShowParent(parent) => {
+        self.parent = parent;
+        true
+      }
+      Msg::ToggleObjectLabels => {
+        self.objects = match self.objects {
+          Some(_) => None,
+          None => Some(vec![]),
+        };
+        self.analyze_board(&settings);
+        true
+      }
+      Msg::ChangeColorMode(color_mode) => {
+        self.color_mode = color_mode;
+        true
+      }
+      Msg::ClickCell(cell) => {
+        if !self.picking_object {
+          // Clicking a labelled object selects it.
+          if self.objects.is_none() {
+            return false;
+          }
+          let object = object_at(&self.cells, cell, settings.object_distance);
+          match bounding_box(&object) {
+            Some(bounds) => self.selection = Some(bounds),
+            None => return false,
+          }
+          return true;
+        }
+        self.picking_object = false;
+        let object = object_at(&self.cells, cell, settings.object_distance);
+        if !object.is_empty() {
+          self.followed_object = Some(object);
+          self.lost_object = false;
+          if self.follow == FollowMode::Off {
+            self.follow = FollowMode::Centroid;
+          }
+          self.follow(&settings);
+        }
+        true
+      }
+      Msg::DrawCell((cell, alive)) => {
+        self.cells = if alive {
+          make_cell_alive(&self.cells, cell)
+        } else {
+          make_cell_dead(&self.cells, cell)
+        };
+        self.load_cells();
+        self.analyze_board(&settings);
+        true
+      }
+      Msg::FitAll => match bounding_box(&self.cells) {
+        Some(bounds) => {
+          let size = (self.width, self.height);
+          self.zoom = zoom_to_fit(bounds, FIT_MARGIN, size, &settings);
+          self.offset = offset_centered_on(center_of(bounds), self.zoom, size, &settings);
+          true
+        }
+        None => {
+          ctx.link().send_message(Msg::ResetView);
+          false
+        }
+      },
+      Msg::ResetView => {
+        self.zoom = 1.0;
+        self.offset =
+          offset_centered_on((0.0, 0.0), self.zoom, (self.width, self.height), &settings);
+        true
+      }
+      Msg::KeyDown(event) => {
+        if is_modifier(&event.key()) {
+          return false;
+        }
+        let key = key_name(
+          &event.key(),
+          event.ctrl_key(),
+          event.alt_key(),
+          event.meta_key(),
+        );
+        if let Some(action) = self.capturing_key.take() {
+          event.prevent_default();
+          if key != "Escape" {
+            let mut key_bindings = settings.key_bindings.clone();
+            key_bindings.bind(&key, action);
+            Component::update(self, ctx, Msg::ChangeKeyBindings(key_bindings));
+          }
+          return true;
+        }
+        if self.palette_open {
+          self.palette_open = key != "Escape";
+          return !self.palette_open;
+        }
+        if self.settings_open && key == "Escape" {
+          self.settings_open = false;
+          return true;
+        }
+        if is_typing(&event) {
+          return false;
+        }
+        match settings.key_bindings.action_for(&key) {
+          Some(action) => {
+            event.prevent_default();
+            Component::update(self, ctx, Msg::RunAction(action))
+          }
+          None => false,
+        }
+      }
+      Msg::RunAction(action) => {
+        let running = self.running;
+        let msg = match action {
+          Action::PlayPause if running => Msg::Pause,
+          Action::PlayPause => Msg::Play,
+          Action::Tick if running => return false,
+          Action::Tick => Msg::NextTick,
+          Action::ZoomIn => self.zoom_message(self.zoom * ZOOM_STEP, &settings),
+          Action::ZoomOut => self.zoom_message(self.zoom / ZOOM_STEP, &settings),
+          Action::PanLeft => Msg::MoveOffset((self.offset.0 + PAN_STEP, self.offset.1)),
+          Action::PanRight => Msg::MoveOffset((self.offset.0 - PAN_STEP, self.offset.1)),
+          Action::PanUp => Msg::MoveOffset((self.offset.0, self.offset.1 + PAN_STEP)),
+          Action::PanDown => Msg::MoveOffset((self.offset.0, self.offset.1 - PAN_STEP)),
+          Action::SlowDown => Msg::ChangeSpeed(self.speed.slower()),
+          Action::SpeedUp => Msg::ChangeSpeed(self.speed.faster()),
+          Action::Recenter => Msg::ResetView,
+          Action::FitAll => Msg::FitAll,
+          Action::CommandPalette => {
+            self.palette_open = true;
+            return true;
+          }
+          Action::OpenSettings => {
+            self.settings_open = true;
+            return true;
+          }
+          Action::LabelObjects => Msg::ToggleObjectLabels,
+        };
+        Component::update(self, ctx, msg)
+      }
+      Msg::ClosePalette => {
+        self.palette_open = false;
+        true
+      }
+      Msg::CloseSettings => {
+        self.settings_open = false;
+        true
+      }
+      Msg::CaptureKey(action) => {
+        self.capturing_key = action;
+        true
+      }
+      Msg::ChangeKeyBindings(key_bindings) => {
+        ctx.props().on_change_settings.emit(Settings {
+          key_bindings,
+          ..settings
+        });
+        true
+      }
+      Msg::ChangeTheme(theme) => {
+        ctx
+          .props()
+          .on_change_settings
+          .emit(Settings { theme, ..settings });
+        true
+      }
     }
   }
 
@@ -149,39 +647,125 @@ impl Component for Game {
     let resize_handle = EventListener::new(&window, "resize", move |_: &Event| {
       link.send_message(Msg::Resize)
     });
+    let link = ctx.link().clone();
+    let engine = Engine::spawner()
+      .callback(move |generation| link.send_message(Msg::ReceiveGeneration(generation)))
+      .spawn(WORKER_PATH);
+    let link = ctx.link().clone();
+    let keydown_handle = EventListener::new_with_options(
+      &window.document().unwrap(),
+      "keydown",
+      EventListenerOptions::enable_prevent_default(),
+      move |event: &Event| {
+        if let Some(event) = event.dyn_ref::<KeyboardEvent>() {
+          link.send_message(Msg::KeyDown(event.clone()))
+        }
+      },
+    );
 
     Self {
       cells: CellSet::new(),
       previous_gens: vec![] as Vec<CellSet>,
       tick: 0,
-      interval: None,
-      speed: 5,
+      running: false,
+      speed: Speed::default(),
+      engine,
+      epoch: 0,
+      pending_generation: None,
+      next_frame: None,
+      frame_gens: 0.0,
+      advancing: false,
+      awaiting_render: false,
+      frames: 0,
+      gens_meter: RateMeter::default(),
+      frames_meter: RateMeter::default(),
+      stop_conditions: StopConditions::default(),
+      stop_notice: None,
       adjust_offset: None,
       offset: (0.0, 0.0),
       zoom: 1.0,
       width: 300,
       height: 200,
+      selection: None,
+      library: UserLibrary::load(),
+      follow: FollowMode::Off,
+      follow_zoom: false,
+      followed_object: None,
+      lost_object: false,
+      picking_object: false,
+      classifier: Classifier::new(Lexicon::get()),
+      objects: None,
+      identified: None,
+      parent: None,
+      color_mode: ColorMode::Trail,
+      palette_open: false,
+      settings_open: false,
+      capturing_key: None,
       _resize_handle: resize_handle,
+      _keydown_handle: keydown_handle,
     }
   }
 
+  fn changed(&mut self, ctx: &Context<Self>) -> bool {
+    // The settings may have changed, e.g. the distance between objects.
+    let settings = self.settings(ctx);
+    self.analyze_board(&settings);
+    // A lower maximum zoom zooms out, around the center of the board.
+    if self.zoom > settings.max_zoom {
+      ctx
+        .link()
+        .send_message(self.zoom_message(self.zoom, &settings));
+    }
+    true
+  }
+
   fn rendered(&mut self, ctx: &Context<Self>, _first_render: bool) {
     if _first_render {
       ctx.link().send_message(Msg::Resize);
     }
+    if self.awaiting_render {
+      self.awaiting_render = false;
+      self.engine.send(Input::Shown);
+    }
   }
 
   fn view(&self, ctx: &Context<Self>) -> yew::virtual_dom::VNode {
-    let running = self.interval.is_some();
+    let running = self.running;
+    let settings = self.settings(ctx);
 
-    let on_change_speed = ctx.link().callback(|event: Event| {
-      let input = event
-        .target()
-        .and_then(|t| t.dyn_into::<HtmlInputElement>().ok())
-        .unwrap();
-      let speed: u8 = input.value().parse().unwrap();
-      Msg::ChangeSpeed(speed)
-    });
+    let on_change_speed_mode = {
+      let speed = self.speed;
+      ctx.link().callback(move |event: Event| {
+        let select = event
+          .target()
+          .and_then(|t| t.dyn_into::<HtmlSelectElement>().ok())
+          .unwrap();
+        Msg::ChangeSpeed(match select.value().as_str() {
+          "target" => Speed::Target(speed.gens_per_second()),
+          _ => speed.as_step_exponent(),
+        })
+      })
+    };
+
+    let on_change_speed = {
+      let speed = self.speed;
+      ctx.link().batch_callback(move |event: Event| {
+        let input = event
+          .target()
+          .and_then(|t| t.dyn_into::<HtmlInputElement>().ok())
+          .unwrap();
+        match speed {
+          Speed::StepExponent(_) => input.value().parse().ok().map(Speed::StepExponent),
+          Speed::Target(_) => input
+            .value()
+            .parse::<f64>()
+            .ok()
+            .filter(|&gens_per_second| gens_per_second > 0.0)
+            .map(|gens_per_second| Speed::Target(gens_per_second.min(Speed::MAX_TARGET))),
+        }
+        .map(Msg::ChangeSpeed)
+      })
+    };
 
     let on_change_zoom = {
       let width = self.width;
@@ -191,7 +775,8 @@ impl Component for Game {
           .target()
           .and_then(|t| t.dyn_into::<HtmlInputElement>().ok())
           .unwrap();
-        let zoom: f64 = input.value().parse().unwrap();
+        // The slider is logarithmic, to reach far zooms.
+        let zoom = 2_f64.powf(input.value().parse().unwrap());
         Msg::ChangeZoom((
           (width as f64 / 2_f64) as i32,
           (height as f64 / 2_f64) as i32,
@@ -200,17 +785,58 @@ impl Component for Game {
       })
     };
 
+    let on_change_follow = ctx.link().callback(|event: Event| {
+      let select = event
+        .target()
+        .and_then(|t| t.dyn_into::<HtmlSelectElement>().ok())
+        .unwrap();
+      Msg::ChangeFollow(match select.value().as_str() {
+        "centroid" => FollowMode::Centroid,
+        "bounding-box" => FollowMode::BoundingBox,
+        _ => FollowMode::Off,
+      })
+    });
+
+    let on_change_follow_zoom = ctx.link().callback(|event: Event| {
+      let input = event
+        .target()
+        .and_then(|t| t.dyn_into::<HtmlInputElement>().ok())
+        .unwrap();
+      Msg::ChangeFollowZoom(input.checked())
+    });
+
     html! {
       <>
         <Board
           cells={self.cells.clone()}
           previous_gens={self.previous_gens.clone()}
+          color_mode={self.color_mode}
           offset={self.offset}
           zoom={self.zoom}
           move_offset={ctx.link().callback(move |offset| Msg::MoveOffset(offset))}
           change_zoom={ctx.link().callback(move |(x1, y1, zoom)| Msg::ChangeZoom((x1, y1, zoom)))}
           width={self.width}
           height={self.height}
+          selection={self.selection}
+          select_region={ctx.link().callback(Msg::SelectRegion)}
+          click_cell={ctx.link().callback(Msg::ClickCell)}
+          draw_cell={ctx.link().callback(Msg::DrawCell)}
+          stop_regions={
+            [self.stop_conditions.region, self.stop_conditions.watched_area]
+              .into_iter()
+              .flatten()
+              .collect::<Vec<_>>()
+          }
+          objects={self.objects.clone().unwrap_or_default()}
+          parent={self.parent.clone()}
+        />
+        <Minimap
+          cells={self.cells.clone()}
+          offset={self.offset}
+          zoom={self.zoom}
+          width={self.width}
+          height={self.height}
+          move_offset={ctx.link().callback(Msg::MoveOffset)}
         />
         <div class="panel">
           <div class="controls">
@@ -224,23 +850,178 @@ impl Component for Game {
             }>{{if running { "Pause" } else { "Play" }}}</button>
             <span class="generation">{format!("Generation #{}", self.tick)}</span>
           </div>
-          <PatternSelector on_apply_pattern={ctx.link().callback(|term| Msg::ApplyPattern(term))} />
-          <label>
+          {match &self.identified {
+            Some(name) => html! { <div class="identified">{format!("This is: {}", name)}</div> },
+            None => html! {},
+          }}
+          {match &self.stop_notice {
+            Some(reason) => html! {
+              <div class="stop-notice">
+                <span>{format!("Stopped: {}", reason)}</span>
+                <button onclick={ctx.link().callback(|_| Msg::DismissStopNotice)}>{"×"}</button>
+              </div>
+            },
+            None => html! {},
+          }}
+          {if self.lost_object {
+            html! {
+              <div class="stop-notice">
+                <span>{"Lost the followed object"}</span>
+                <button onclick={ctx.link().callback(|_| Msg::DismissLostObject)}>{"×"}</button>
+              </div>
+            }
+          } else {
+            html! {}
+          }}
+          <PatternSelector
+            user_library={self.library.clone()}
+            on_apply_pattern={ctx.link().callback(|term| Msg::ApplyPattern(term))}
+          />
+          <ApgcodeInput on_apply_pattern={ctx.link().callback(Msg::ApplyPattern)} />
+          <SoupPanel on_apply_pattern={ctx.link().callback(Msg::ApplyPattern)} />
+          <CensusPanel />
+          <PredecessorPanel
+            cells={self.cells.clone()}
+            selection={self.selection}
+            on_show_parent={ctx.link().callback(Msg::ShowParent)}
+            on_apply_pattern={ctx.link().callback(Msg::ApplyPattern)}
+          />
+          <div class="speed">
             <span>{"Speed"}</span>
-            <input
-              type="range" min="1" max="10"
-              value={self.speed.to_string()}
-              onchange={on_change_speed}
-            />
-          </label>
-          <label>
-            <span>{"Zoom"}</span>
-            <input
-              type="range" min="0.1" max="5.0" step="0.1"
-              value={self.zoom.to_string()}
-              onchange={on_change_zoom}
-            />
-          </label>
+            <select onchange={on_change_speed_mode}>
+              <option value="step" selected={matches!(self.speed, Speed::StepExponent(_))}>{"Step"}</option>
+              <option value="target" selected={matches!(self.speed, Speed::Target(_))}>{"Gen/s"}</option>
+            </select>
+            {match self.speed {
+              Speed::StepExponent(exponent) => html! {
+                <input
+                  type="range" min={Speed::MIN_EXPONENT.to_string()} max={Speed::MAX_EXPONENT.to_string()}
+                  title="Step exponent: 60 × 2^N generations per second"
+                  value={exponent.to_string()}
+                  onchange={on_change_speed}
+                />
+              },
+              Speed::Target(gens_per_second) => html! {
+                <input
+                  type="number" min="1" max={Speed::MAX_TARGET.to_string()}
+                  value={gens_per_second.to_string()}
+                  onchange={on_change_speed}
+                />
+              },
+            }}
+            <span class="unit">{self.speed.label()}</span>
+          </div>
+          {match (running, self.gens_meter.rate(), self.frames_meter.rate()) {
+            (true, Some(gens_per_second), Some(frames_per_second)) => html! {
+              <div class="rates">
+                {format!("{:.1} gen/s · {:.0} fps", gens_per_second, frames_per_second)}
+              </div>
+            },
+            _ => html! {},
+          }}
+          <div class="zoom">
+            <label>
+              <span>{"Zoom"}</span>
+              <input
+                type="range" min={MIN_ZOOM.log2().to_string()} max={settings.max_zoom.log2().to_string()} step="0.1"
+                value={self.zoom.log2().to_string()}
+                onchange={on_change_zoom}
+              />
+            </label>
+            {if pitch(self.zoom, &settings) < 1.0 {
+              let cells_per_pixel = (1.0 / pitch(self.zoom, &settings)).round();
+              html! {
+                <span class="unit" title={format!("A pixel shows {0}×{0} cells", cells_per_pixel)}>
+                  {format!("1:{}", cells_per_pixel)}
+                </span>
+              }
+            } else {
+              html! {}
+            }}
+            <button title="Zoom to fit the whole population" onclick={ctx.link().callback(|_| Msg::FitAll)}>{"Fit all"}</button>
+            <button title="Back to the origin at zoom 1" onclick={ctx.link().callback(|_| Msg::ResetView)}>{"Reset"}</button>
+          </div>
+          <div class="follow">
+            <span>{"Follow"}</span>
+            <select onchange={on_change_follow}>
+              <option value="off" selected={self.follow == FollowMode::Off}>{"Off"}</option>
+              <option value="centroid" selected={self.follow == FollowMode::Centroid}>{"Centroid"}</option>
+              <option value="bounding-box" selected={self.follow == FollowMode::BoundingBox}>{"Bounding box"}</option>
+            </select>
+            <label class="checkbox" title="Zoom to fit the followed pattern">
+              <input type="checkbox" checked={self.follow_zoom} onchange={on_change_follow_zoom} />
+              {"Fit"}
+            </label>
+            <button
+              class={classes!(self.picking_object.then_some("active"))}
+              onclick={ctx.link().callback(|_| Msg::PickObject)}
+            >
+              {if self.picking_object {
+                "Click an object…"
+              } else if self.followed_object.is_some() {
+                "Pick another"
+              } else {
+                "Pick object"
+              }}
+            </button>
+          </div>
+          <div class="objects">
+            <label class="checkbox" title="Split the board into objects and name them">
+              <input
+                type="checkbox"
+                checked={self.objects.is_some()}
+                onchange={ctx.link().callback(|_| Msg::ToggleObjectLabels)}
+              />
+              {"Label objects"}
+            </label>
+            {match &self.objects {
+              Some(objects) => html! {
+                <span class="unit">{format!("{} objects, click one to select it", objects.len())}</span>
+              },
+              None => html! {},
+            }}
+          </div>
+          <StopPanel
+            conditions={self.stop_conditions.clone()}
+            selection={self.selection}
+            on_change={ctx.link().callback(Msg::ChangeStopConditions)}
+          />
+          <ColorModePanel
+            color_mode={self.color_mode}
+            on_change={ctx.link().callback(Msg::ChangeColorMode)}
+          />
+          <ExportPanel
+            cells={self.cells.clone()}
+            previous_gens={self.previous_gens.clone()}
+            offset={self.offset}
+            zoom={self.zoom}
+            width={self.width}
+            height={self.height}
+          />
+          <LibraryPanel
+            cells={self.cells.clone()}
+            selection={self.selection}
+            library={self.library.clone()}
+            on_change_library={ctx.link().callback(Msg::ChangeLibrary)}
+            on_clear_selection={ctx.link().callback(|_| Msg::SelectRegion(None))}
+          />
+          <KeyBindingsPanel
+            key_bindings={settings.key_bindings.clone()}
+            capturing={self.capturing_key}
+            on_capture={ctx.link().callback(Msg::CaptureKey)}
+            on_change={ctx.link().callback(Msg::ChangeKeyBindings)}
+          />
+          <ThemePanel
+            choice={settings.theme}
+            theme={self.theme(ctx)}
+            on_change={ctx.link().callback(Msg::ChangeTheme)}
+          />
+          <button
+            class="open-settings"
+            onclick={ctx.link().callback(|_| Msg::RunAction(Action::OpenSettings))}
+          >
+            {"Settings…"}
+          </button>
           <div class="about">
             {"Made by "}
             <a href="https://twitter.com/scastiel" target="_blank" rel="noopener noreferrer">{"Sébastien Castiel"}</a>
@@ -248,6 +1029,29 @@ impl Component for Game {
             <a href="https://github.com/scastiel/lifeee-rs" target="_blank" rel="noopener noreferrer">{"About"}</a>
           </div>
         </div>
+        {if self.palette_open {
+          html! {
+            <CommandPalette
+              key_bindings={settings.key_bindings.clone()}
+              on_action={ctx.link().callback(Msg::RunAction)}
+              on_apply_pattern={ctx.link().callback(Msg::ApplyPattern)}
+              on_close={ctx.link().callback(|_| Msg::ClosePalette)}
+            />
+          }
+        } else {
+          html! {}
+        }}
+        {if self.settings_open {
+          html! {
+            <SettingsDialog
+              settings={settings.clone()}
+              on_change={ctx.props().on_change_settings.clone()}
+              on_close={ctx.link().callback(|_| Msg::CloseSettings)}
+            />
+          }
+        } else {
+          html! {}
+        }}
         <a href="https://github.com/scastiel/lifeee-rs" target="_blank" rel="noopener noreferrer" aria-label="Lifeee GitHub repository" style="position: fixed; top: 0; right: 0">
           <svg xmlns="http://www.w3.org/2000/svg" width="40" height="40" viewBox="0 0 250 250"> <path d="M0 0l115 115h15l12 27 108 108V0z" fill="#868e96"></path> <path d="M128 109c-15-9-9-19-9-19 3-7 2-11 2-11-1-7 3-2 3-2 4 5 2 11 2 11-3 10 5 15 9 16" fill="#ffffff" style="transform-origin:130px 106px"></path> <path d="M115 115s4 2 5 0l14-14c3-2 6-3 8-3-8-11-15-24 2-41 5-5 10-7 16-7 1-2 3-7 12-11 0 0 5 3 7 16 4 2 8 5 12 9s7 8 9 12c14 3 17 7 17 7-4 8-9 11-11 11 0 6-2 11-7 16-16 16-30 10-41 2 0 3-1 7-5 11l-12 11c-1 1 1 5 1 5z" fill="#ffffff"></path> </svg>
         </a>
@@ -0,0 +1,86 @@
+use crate::keybindings::{Action, KeyBindings};
+use yew::prelude::*;
+
+/// Lists the keyboard shortcuts and lets the user change them. The next key
+/// pressed while an action is waiting for a key is captured by `Game`.
+pub struct KeyBindingsPanel;
+
+#[derive(Properties, PartialEq)]
+pub struct Props {
+  pub key_bindings: KeyBindings,
+  /// Action waiting for a new key, if any.
+  pub capturing: Option<Action>,
+  pub on_capture: Callback<Option<Action>>,
+  pub on_change: Callback<KeyBindings>,
+}
+
+pub enum Msg {
+  Capture(Action),
+  Unbind(String),
+  Reset,
+}
+
+impl Component for KeyBindingsPanel {
+  type Message = Msg;
+  type Properties = Props;
+
+  fn create(_: &Context<Self>) -> Self {
+    Self
+  }
+
+  fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+    match msg {
+      Msg::Capture(action) => {
+        let capturing = if ctx.props().capturing == Some(action) {
+          None
+        } else {
+          Some(action)
+        };
+        ctx.props().on_capture.emit(capturing);
+      }
+      Msg::Unbind(key) => {
+        let mut key_bindings = ctx.props().key_bindings.clone();
+        key_bindings.unbind(&key);
+        ctx.props().on_change.emit(key_bindings);
+      }
+      Msg::Reset => ctx.props().on_change.emit(KeyBindings::default()),
+    }
+    false
+  }
+
+  fn view(&self, ctx: &Context<Self>) -> Html {
+    let key_bindings = &ctx.props().key_bindings;
+    html! {
+      <details class="key-bindings-panel">
+        <summary>{"Keyboard shortcuts"}</summary>
+        <ul class="key-bindings">
+          {for Action::ALL.iter().map(|&action| html! {
+            <li>
+              <span class="key-binding-action">{action.label()}</span>
+              {for key_bindings.keys_for(action).into_iter().map(|key| {
+                let unbound = key.to_string();
+                html! {
+                  <span
+                    class="tag"
+                    title="Remove this shortcut"
+                    onclick={ctx.link().callback(move |_| Msg::Unbind(unbound.clone()))}
+                  >
+                    {format!("{} ×", key)}
+                  </span>
+                }
+              })}
+              <button
+                class={classes!((ctx.props().capturing == Some(action)).then_some("active"))}
+                title="Add a shortcut"
+                onclick={ctx.link().callback(move |_| Msg::Capture(action))}
+              >
+                {if ctx.props().capturing == Some(action) { "Press a key…" } else { "+" }}
+              </button>
+            </li>
+          })}
+        </ul>
+        <button onclick={ctx.link().callback(|_| Msg::Reset)}>{"Reset to defaults"}</button>
+      </details>
+    }
+  }
+}
@@ -0,0 +1,265 @@
+use crate::download::download_file;
+use crate::lexicon::Cell;
+use crate::library::*;
+use crate::life::CellSet;
+use gloo::file::callbacks::FileReader;
+use gloo::file::File;
+use wasm_bindgen::JsCast;
+use web_sys::{HtmlInputElement, HtmlSelectElement, HtmlTextAreaElement};
+use yew::prelude::*;
+
+/// Saves the board (or the selected region) to the user library, and
+/// organises, imports and exports the library.
+pub struct LibraryPanel {
+  name: String,
+  folder: String,
+  tags: String,
+  description: String,
+  message: Option<String>,
+  reader: Option<FileReader>,
+}
+
+#[derive(Properties, PartialEq)]
+pub struct Props {
+  pub cells: CellSet,
+  pub selection: Option<(Cell, Cell)>,
+  pub library: UserLibrary,
+  pub on_change_library: Callback<UserLibrary>,
+  pub on_clear_selection: Callback<()>,
+}
+
+pub enum Msg {
+  ChangeName(String),
+  ChangeFolder(String),
+  ChangeTags(String),
+  ChangeDescription(String),
+  Save,
+  Import(File),
+  Imported(Result<UserLibrary, String>),
+  ExportJson,
+  ExportZip,
+  Move(usize, String),
+  Delete(usize),
+}
+
+fn input_value(event: InputEvent) -> String {
+  event
+    .target()
+    .and_then(|t| t.dyn_into::<HtmlInputElement>().ok())
+    .unwrap()
+    .value()
+}
+
+impl LibraryPanel {
+  fn change_library(&self, ctx: &Context<Self>, change: impl FnOnce(&mut UserLibrary)) {
+    let mut library = ctx.props().library.clone();
+    change(&mut library);
+    ctx.props().on_change_library.emit(library);
+  }
+
+  fn view_pattern(&self, ctx: &Context<Self>, i: usize, pattern: &UserPattern) -> Html {
+    let folders = ctx.props().library.folders();
+    let on_move = ctx.link().callback(move |event: Event| {
+      let select = event
+        .target()
+        .and_then(|t| t.dyn_into::<HtmlSelectElement>().ok())
+        .unwrap();
+      Msg::Move(i, select.value())
+    });
+    html! {
+      <li>
+        <span class="user-pattern-name">{pattern.term.name.clone()}</span>
+        <select onchange={on_move} title="Move to folder">
+          <option value="" selected={pattern.folder.is_empty()}>{"(no folder)"}</option>
+          {for folders.iter().map(|folder| html! {
+            <option value={folder.clone()} selected={*folder == pattern.folder}>{folder.clone()}</option>
+          })}
+        </select>
+        <button title="Delete" onclick={ctx.link().callback(move |_| Msg::Delete(i))}>{"×"}</button>
+      </li>
+    }
+  }
+}
+
+impl Component for LibraryPanel {
+  type Message = Msg;
+  type Properties = Props;
+
+  fn create(_: &Context<Self>) -> Self {
+    Self {
+      name: String::new(),
+      folder: String::new(),
+      tags: String::new(),
+      description: String::new(),
+      message: None,
+      reader: None,
+    }
+  }
+
+  fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+    match msg {
+      Msg::ChangeName(name) => self.name = name,
+      Msg::ChangeFolder(folder) => self.folder = folder,
+      Msg::ChangeTags(tags) => self.tags = tags,
+      Msg::ChangeDescription(description) => self.description = description,
+      Msg::Save => {
+        let tags = self
+          .tags
+          .split(',')
+          .map(|tag| tag.trim().to_string())
+          .filter(|tag| !tag.is_empty())
+          .collect();
+        let term = term_from_cells(
+          self.name.trim().to_string(),
+          self.description.trim().to_string(),
+          tags,
+          &ctx.props().cells,
+          ctx.props().selection,
+        );
+        if term.cells.is_empty() {
+          self.message = Some("Nothing to save, the board is empty.".to_string());
+          return true;
+        }
+        let pattern = UserPattern {
+          folder: self.folder.trim().to_string(),
+          term,
+        };
+        self.message = Some(format!("Saved “{}”.", pattern.term.name));
+        self.change_library(ctx, |library| library.add(pattern));
+        ctx.props().on_clear_selection.emit(());
+        self.name.clear();
+        self.description.clear();
+      }
+      Msg::Import(file) => {
+        let link = ctx.link().clone();
+        let file_name = file.name();
+        self.reader = Some(gloo::file::callbacks::read_as_bytes(&file, move |result| {
+          let library = result.map_err(|error| error.to_string()).and_then(|bytes| {
+            let lowercase_name = file_name.to_lowercase();
+            if lowercase_name.ends_with(".zip") {
+              UserLibrary::from_zip(&bytes)
+            } else {
+              let source = String::from_utf8_lossy(&bytes);
+              if lowercase_name.ends_with(".json") {
+                UserLibrary::from_json(&source)
+              } else {
+                pattern_from_rle(&file_name, &source).map(|pattern| UserLibrary {
+                  patterns: vec![pattern],
+                })
+              }
+            }
+          });
+          link.send_message(Msg::Imported(library));
+        }));
+      }
+      Msg::Imported(result) => {
+        self.reader = None;
+        match result {
+          Ok(imported) => {
+            self.message = Some(format!("Imported {} patterns.", imported.patterns.len()));
+            self.change_library(ctx, |library| library.merge(imported));
+          }
+          Err(error) => self.message = Some(format!("Import failed: {}", error)),
+        }
+      }
+      Msg::ExportJson => {
+        let json = ctx.props().library.to_json();
+        download_file(json.as_bytes(), "application/json", "lifeee-library.json");
+      }
+      Msg::ExportZip => {
+        let zip = ctx.props().library.to_zip();
+        download_file(&zip, "application/zip", "lifeee-library.zip");
+      }
+      Msg::Move(i, folder) => self.change_library(ctx, |library| {
+        library.patterns[i].folder = folder;
+      }),
+      Msg::Delete(i) => self.change_library(ctx, |library| {
+        library.patterns.remove(i);
+      }),
+    }
+    true
+  }
+
+  fn view(&self, ctx: &Context<Self>) -> Html {
+    let library = &ctx.props().library;
+    let on_import = ctx.link().batch_callback(|event: Event| {
+      let input = event
+        .target()
+        .and_then(|t| t.dyn_into::<HtmlInputElement>().ok())
+        .unwrap();
+      let file = input.files().and_then(|files| files.get(0));
+      input.set_value("");
+      file
+        .map(|file| Msg::Import(File::from(file)))
+        .into_iter()
+        .collect::<Vec<_>>()
+    });
+    let on_change_description = ctx.link().callback(|event: InputEvent| {
+      let textarea = event
+        .target()
+        .and_then(|t| t.dyn_into::<HtmlTextAreaElement>().ok())
+        .unwrap();
+      Msg::ChangeDescription(textarea.value())
+    });
+
+    let mut folders = vec![String::new()];
+    folders.extend(library.folders());
+
+    html! {
+      <details class="library-panel">
+        <summary>{format!("My patterns ({})", library.patterns.len())}</summary>
+        <div class="library-form">
+          <input
+            placeholder="Name"
+            value={self.name.clone()}
+            oninput={ctx.link().callback(|event: InputEvent| Msg::ChangeName(input_value(event)))}
+          />
+          <input
+            placeholder="Folder"
+            list="library-folders"
+            value={self.folder.clone()}
+            oninput={ctx.link().callback(|event: InputEvent| Msg::ChangeFolder(input_value(event)))}
+          />
+          <datalist id="library-folders">
+            {for library.folders().into_iter().map(|folder| html! { <option value={folder} /> })}
+          </datalist>
+          <input
+            placeholder="Tags, separated by commas"
+            value={self.tags.clone()}
+            oninput={ctx.link().callback(|event: InputEvent| Msg::ChangeTags(input_value(event)))}
+          />
+          <textarea
+            placeholder="Description"
+            value={self.description.clone()}
+            oninput={on_change_description}
+          />
+          <button
+            disabled={self.name.trim().is_empty()}
+            onclick={ctx.link().callback(|_| Msg::Save)}
+          >
+            {if ctx.props().selection.is_some() { "Save selection" } else { "Save board" }}
+          </button>
+          <span class="hint">{"Shift + drag on the board to select a region."}</span>
+        </div>
+        {for self.message.iter().map(|message| html! { <div class="hint">{message.clone()}</div> })}
+        {for folders.iter().map(|folder| {
+          let patterns = library.patterns.iter().enumerate().filter(|(_, pattern)| pattern.folder == *folder);
+          html! {
+            <ul class="user-patterns">
+              {if folder.is_empty() { html! {} } else { html! { <li class="folder">{folder.clone()}</li> } }}
+              {for patterns.map(|(i, pattern)| self.view_pattern(ctx, i, pattern))}
+            </ul>
+          }
+        })}
+        <div class="library-actions">
+          <label class="import">
+            <span>{"Import…"}</span>
+            <input type="file" accept=".json,.zip,.rle" onchange={on_import} />
+          </label>
+          <button onclick={ctx.link().callback(|_| Msg::ExportJson)}>{"Export JSON"}</button>
+          <button onclick={ctx.link().callback(|_| Msg::ExportZip)}>{"Export ZIP"}</button>
+        </div>
+      </details>
+    }
+  }
+}
@@ -0,0 +1,211 @@
+use crate::color_utils::to_hex;
+use crate::life::{bounding_box, CellSet};
+use crate::settings::Settings;
+use crate::theme::Theme;
+use crate::viewport::{offset_centered_on, visible_area};
+use wasm_bindgen::*;
+use yew::prelude::*;
+
+const MINIMAP_WIDTH: u32 = 160;
+const MINIMAP_HEIGHT: u32 = 120;
+/// Space kept around the population and the view, as a fraction of their
+/// size.
+const MARGIN: f64 = 0.05;
+
+/// Overview of the whole population with the part shown on the board.
+/// Clicking or dragging in it moves the board there.
+pub struct Minimap {
+  canvas_ref: NodeRef,
+  /// Frame used while dragging, so that the map doesn't move under the
+  /// pointer as the view does.
+  drag_frame: Option<Frame>,
+}
+
+/// Mapping from cells to minimap pixels: cell coordinates of the top-left
+/// corner and pixels per cell.
+#[derive(Clone, Copy)]
+struct Frame {
+  x: f64,
+  y: f64,
+  scale: f64,
+}
+
+#[derive(Properties, PartialEq)]
+pub struct Props {
+  pub cells: CellSet,
+  pub offset: (f64, f64),
+  pub zoom: f64,
+  pub width: u32,
+  pub height: u32,
+  pub move_offset: Callback<(f64, f64)>,
+}
+
+#[allow(clippy::enum_variant_names)]
+pub enum Msg {
+  PointerDown(i32, i32),
+  PointerMove(i32, i32),
+  PointerUp,
+}
+
+impl Minimap {
+  fn theme(&self, ctx: &Context<Self>) -> Theme {
+    ctx
+      .link()
+      .context::<Theme>(Callback::noop())
+      .expect("theme context to be set")
+      .0
+  }
+
+  fn settings(&self, ctx: &Context<Self>) -> Settings {
+    ctx
+      .link()
+      .context::<Settings>(Callback::noop())
+      .expect("settings context to be set")
+      .0
+  }
+
+  /// Frame fitting both the population and the visible area.
+  fn frame(&self, ctx: &Context<Self>) -> Frame {
+    let props = ctx.props();
+    let settings = self.settings(ctx);
+    let view = visible_area(
+      props.offset,
+      props.zoom,
+      (props.width, props.height),
+      &settings,
+    );
+    let (mut min_x, mut min_y) = (view.x, view.y);
+    let (mut max_x, mut max_y) = (view.x + view.width, view.y + view.height);
+    if let Some((min, max)) = bounding_box(&props.cells) {
+      min_x = min_x.min(min.x as f64);
+      min_y = min_y.min(min.y as f64);
+      max_x = max_x.max((max.x + 1) as f64);
+      max_y = max_y.max((max.y + 1) as f64);
+    }
+    let margin = MARGIN * f64::max(max_x - min_x, max_y - min_y);
+    let scale = f64::min(
+      MINIMAP_WIDTH as f64 / (max_x - min_x + 2.0 * margin),
+      MINIMAP_HEIGHT as f64 / (max_y - min_y + 2.0 * margin),
+    );
+    Frame {
+      x: (min_x + max_x) / 2.0 - MINIMAP_WIDTH as f64 / 2.0 / scale,
+      y: (min_y + max_y) / 2.0 - MINIMAP_HEIGHT as f64 / 2.0 / scale,
+      scale,
+    }
+  }
+
+  /// Centers the board on the cell under the minimap pixel `(x, y)`.
+  fn navigate(&self, ctx: &Context<Self>, frame: Frame, x: i32, y: i32) {
+    let props = ctx.props();
+    let center = (
+      frame.x + x as f64 / frame.scale,
+      frame.y + y as f64 / frame.scale,
+    );
+    props.move_offset.emit(offset_centered_on(
+      center,
+      props.zoom,
+      (props.width, props.height),
+      &self.settings(ctx),
+    ));
+  }
+
+  fn draw(&self, ctx: &Context<Self>) {
+    let canvas = match self.canvas_ref.cast::<web_sys::HtmlCanvasElement>() {
+      Some(canvas) => canvas,
+      None => return,
+    };
+    let context = canvas
+      .get_context("2d")
+      .unwrap()
+      .unwrap()
+      .dyn_into::<web_sys::CanvasRenderingContext2d>()
+      .unwrap();
+    let props = ctx.props();
+    let theme = self.theme(ctx);
+    let frame = self.drag_frame.unwrap_or_else(|| self.frame(ctx));
+
+    context.set_fill_style(&JsValue::from(to_hex(theme.background)));
+    context.fill_rect(0.0, 0.0, MINIMAP_WIDTH as f64, MINIMAP_HEIGHT as f64);
+
+    // Cells smaller than a pixel are still drawn as one.
+    let size = frame.scale.max(1.0);
+    context.set_fill_style(&JsValue::from(to_hex(theme.live_cell)));
+    for cell in &props.cells {
+      context.fill_rect(
+        (cell.x as f64 - frame.x) * frame.scale,
+        (cell.y as f64 - frame.y) * frame.scale,
+        size,
+        size,
+      );
+    }
+
+    let view = visible_area(
+      props.offset,
+      props.zoom,
+      (props.width, props.height),
+      &self.settings(ctx),
+    );
+    context.set_stroke_style(&JsValue::from(to_hex(theme.live_cell)));
+    context.set_line_width(1.0);
+    context.stroke_rect(
+      (view.x - frame.x) * frame.scale,
+      (view.y - frame.y) * frame.scale,
+      view.width * frame.scale,
+      view.height * frame.scale,
+    );
+  }
+}
+
+impl Component for Minimap {
+  type Message = Msg;
+  type Properties = Props;
+
+  fn create(_: &Context<Self>) -> Self {
+    Self {
+      canvas_ref: NodeRef::default(),
+      drag_frame: None,
+    }
+  }
+
+  fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+    match msg {
+      Msg::PointerDown(x, y) => {
+        let frame = self.frame(ctx);
+        self.drag_frame = Some(frame);
+        self.navigate(ctx, frame, x, y);
+        false
+      }
+      Msg::PointerMove(x, y) => {
+        if let Some(frame) = self.drag_frame {
+          self.navigate(ctx, frame, x, y);
+        }
+        false
+      }
+      Msg::PointerUp => {
+        self.drag_frame = None;
+        true
+      }
+    }
+  }
+
+  fn rendered(&mut self, ctx: &Context<Self>, _first_render: bool) {
+    self.draw(ctx);
+  }
+
+  fn view(&self, ctx: &Context<Self>) -> Html {
+    html! {
+      <div class="minimap">
+        <canvas
+          ref={self.canvas_ref.clone()}
+          width={MINIMAP_WIDTH.to_string()}
+          height={MINIMAP_HEIGHT.to_string()}
+          onpointerdown={ctx.link().callback(|event: PointerEvent| Msg::PointerDown(event.offset_x(), event.offset_y()))}
+          onpointermove={ctx.link().callback(|event: PointerEvent| Msg::PointerMove(event.offset_x(), event.offset_y()))}
+          onpointerup={ctx.link().callback(|_| Msg::PointerUp)}
+          onpointerleave={ctx.link().callback(|_| Msg::PointerUp)}
+        />
+        <span class="population">{format!("Population {}", ctx.props().cells.len())}</span>
+      </div>
+    }
+  }
+}
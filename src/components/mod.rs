@@ -1,3 +1,17 @@
+pub mod apgcode_input;
 pub mod board;
+pub mod census_panel;
+pub mod color_mode_panel;
+pub mod command_palette;
+pub mod export_panel;
 pub mod game;
+pub mod key_bindings_panel;
+pub mod library_panel;
+pub mod minimap;
+pub mod pattern_preview;
 pub mod pattern_selector;
+pub mod predecessor_panel;
+pub mod settings_dialog;
+pub mod soup_panel;
+pub mod stop_panel;
+pub mod theme_panel;
@@ -0,0 +1,136 @@
+use crate::color_utils::to_hex;
+use crate::export::image_bounds;
+use crate::lexicon::Cell;
+use crate::life::{tick, CellSet};
+use crate::theme::Theme;
+use gloo::timers::callback::Interval;
+use wasm_bindgen::*;
+use yew::prelude::*;
+
+const PREVIEW_GENERATIONS: usize = 16;
+const FRAME_MILLIS: u32 = 250;
+
+/// Small animated canvas showing the first generations of a pattern.
+pub struct PatternPreview {
+  canvas_ref: NodeRef,
+  frames: Vec<CellSet>,
+  bounds: (Cell, Cell),
+  frame: usize,
+  _interval: Interval,
+}
+
+#[derive(Properties, PartialEq)]
+pub struct Props {
+  pub cells: Vec<Cell>,
+  pub width: u32,
+  pub height: u32,
+}
+
+pub enum Msg {
+  NextFrame,
+}
+
+impl PatternPreview {
+  fn compute_frames(cells: &[Cell]) -> (Vec<CellSet>, (Cell, Cell)) {
+    let mut frames = vec![cells.iter().copied().collect::<CellSet>()];
+    for _ in 1..PREVIEW_GENERATIONS {
+      frames.push(tick(frames.last().unwrap()));
+    }
+    let bounds = image_bounds(&frames, 1);
+    (frames, bounds)
+  }
+
+  fn theme(&self, ctx: &Context<Self>) -> Theme {
+    ctx
+      .link()
+      .context::<Theme>(Callback::noop())
+      .expect("theme context to be set")
+      .0
+  }
+
+  fn draw(&self, ctx: &Context<Self>) {
+    let canvas = match self.canvas_ref.cast::<web_sys::HtmlCanvasElement>() {
+      Some(canvas) => canvas,
+      None => return,
+    };
+    let context = canvas
+      .get_context("2d")
+      .unwrap()
+      .unwrap()
+      .dyn_into::<web_sys::CanvasRenderingContext2d>()
+      .unwrap();
+    let theme = self.theme(ctx);
+    let (width, height) = (ctx.props().width as f64, ctx.props().height as f64);
+    context.set_fill_style(&JsValue::from(to_hex(theme.background)));
+    context.fill_rect(0.0, 0.0, width, height);
+
+    let (min, max) = self.bounds;
+    let columns = (max.x - min.x + 1) as f64;
+    let rows = (max.y - min.y + 1) as f64;
+    let cell_size = f64::min(width / columns, height / rows);
+    let origin = (
+      (width - cell_size * columns) / 2.0,
+      (height - cell_size * rows) / 2.0,
+    );
+    // Leave a gap between cells when they are big enough for it to show.
+    let gap = if cell_size >= 4.0 { 1.0 } else { 0.0 };
+    context.set_fill_style(&JsValue::from(to_hex(theme.live_cell)));
+    for cell in &self.frames[self.frame] {
+      context.fill_rect(
+        origin.0 + (cell.x - min.x) as f64 * cell_size,
+        origin.1 + (cell.y - min.y) as f64 * cell_size,
+        cell_size - gap,
+        cell_size - gap,
+      );
+    }
+  }
+}
+
+impl Component for PatternPreview {
+  type Message = Msg;
+  type Properties = Props;
+
+  fn create(ctx: &Context<Self>) -> Self {
+    let (frames, bounds) = Self::compute_frames(&ctx.props().cells);
+    let link = ctx.link().clone();
+    Self {
+      canvas_ref: NodeRef::default(),
+      frames,
+      bounds,
+      frame: 0,
+      _interval: Interval::new(FRAME_MILLIS, move || link.send_message(Msg::NextFrame)),
+    }
+  }
+
+  fn update(&mut self, _ctx: &Context<Self>, msg: Self::Message) -> bool {
+    match msg {
+      Msg::NextFrame => {
+        self.frame = (self.frame + 1) % self.frames.len();
+        true
+      }
+    }
+  }
+
+  fn changed(&mut self, ctx: &Context<Self>) -> bool {
+    let (frames, bounds) = Self::compute_frames(&ctx.props().cells);
+    self.frames = frames;
+    self.bounds = bounds;
+    self.frame = 0;
+    true
+  }
+
+  fn rendered(&mut self, ctx: &Context<Self>, _first_render: bool) {
+    self.draw(ctx);
+  }
+
+  fn view(&self, ctx: &Context<Self>) -> Html {
+    html! {
+      <canvas
+        ref={self.canvas_ref.clone()}
+        class="pattern-preview"
+        width={ctx.props().width.to_string()}
+        height={ctx.props().height.to_string()}
+      />
+    }
+  }
+}
@@ -1,32 +1,156 @@
-use lexicon::*;
+use crate::components::pattern_preview::PatternPreview;
+use crate::description::{fragments, Fragment};
+use crate::lexicon::*;
+use crate::library::UserLibrary;
+use crate::search::*;
+use std::collections::HashMap;
 use wasm_bindgen::JsCast;
-use web_sys::HtmlSelectElement;
+use web_sys::{HtmlInputElement, HtmlSelectElement};
 use yew::prelude::*;
 
+/// Results beyond this number aren't rendered, to keep the list responsive.
+const MAX_DISPLAYED_RESULTS: usize = 200;
+
 pub struct PatternSelector {
-  lexicon: Lexicon,
+  /// Terms of the lexicon, followed by the ones of the user library.
+  terms: Vec<Term>,
+  num_lexicon_terms: usize,
+  /// Folder of each term of the user library.
+  user_folders: Vec<String>,
+  source: Source,
+  /// Index of each term by lowercase name, to resolve cross-references.
+  by_name: HashMap<String, usize>,
+  tags: Vec<String>,
+  query: Query,
+  results: Vec<usize>,
   selected: Option<usize>,
 }
 
 #[derive(Properties, PartialEq)]
 pub struct Props {
+  pub user_library: UserLibrary,
   pub on_apply_pattern: Callback<Term>,
 }
 
+/// Where the listed patterns come from.
+#[derive(Clone, PartialEq)]
+pub enum Source {
+  All,
+  Lexicon,
+  User,
+  Folder(String),
+}
+
 pub enum Msg {
   PatternChanged(usize),
   Apply,
+  ChangeText(String),
+  AddTag(String),
+  RemoveTag(String),
+  ChangeSort(SortOrder),
+  ChangeSource(Source),
+}
+
+impl PatternSelector {
+  fn update_results(&mut self) {
+    let num_lexicon_terms = self.num_lexicon_terms;
+    let user_folders = &self.user_folders;
+    let source = &self.source;
+    self.results = search(&self.terms, &self.query)
+      .into_iter()
+      .filter(|&i| match source {
+        Source::All => true,
+        Source::Lexicon => i < num_lexicon_terms,
+        Source::User => i >= num_lexicon_terms,
+        Source::Folder(folder) => {
+          i >= num_lexicon_terms && user_folders[i - num_lexicon_terms] == *folder
+        }
+      })
+      .collect();
+  }
+
+  fn set_user_library(&mut self, user_library: &UserLibrary) {
+    self.terms.truncate(self.num_lexicon_terms);
+    self.terms.extend(
+      user_library
+        .patterns
+        .iter()
+        .map(|pattern| pattern.term.clone()),
+    );
+    self.user_folders = user_library
+      .patterns
+      .iter()
+      .map(|pattern| pattern.folder.clone())
+      .collect();
+    self.tags = all_tags(&self.terms);
+    if let Source::Folder(folder) = &self.source {
+      if !self.user_folders.contains(folder) {
+        self.source = Source::User;
+      }
+    }
+    self.selected = self
+      .selected
+      .filter(|&selected| selected < self.terms.len());
+    self.update_results();
+  }
+
+  fn view_details(&self, ctx: &Context<Self>, term: &Term) -> Html {
+    let description = fragments(&term.description).into_iter().map(|fragment| match fragment {
+      Fragment::Text(text) => html! { {text} },
+      Fragment::Link(name) => match self.by_name.get(&name.to_lowercase()) {
+        Some(&i) => html! {
+          <a class="term-link" onclick={ctx.link().callback(move |_| Msg::PatternChanged(i))}>{name}</a>
+        },
+        None => html! { <em>{name}</em> },
+      },
+    });
+
+    html! {
+      <div class="term-details">
+        {if term.cells.is_empty() {
+          html! {}
+        } else {
+          html! { <PatternPreview cells={term.cells.clone()} width={120} height={90} /> }
+        }}
+        <div class="term-description">
+          <strong>{term.name.clone()}</strong>
+          <p>{for description}</p>
+        </div>
+      </div>
+    }
+  }
 }
 
 impl Component for PatternSelector {
   type Message = Msg;
   type Properties = Props;
 
-  fn create(_: &Context<Self>) -> Self {
-    Self {
-      lexicon: Lexicon::get(),
+  fn create(ctx: &Context<Self>) -> Self {
+    let lexicon = Lexicon::get();
+    let by_name = lexicon
+      .terms
+      .iter()
+      .enumerate()
+      .map(|(i, term)| (term.name.to_lowercase(), i))
+      .collect();
+    let mut selector = Self {
+      num_lexicon_terms: lexicon.terms.len(),
+      terms: lexicon.terms.clone(),
+      user_folders: vec![],
+      source: Source::All,
+      by_name,
+      tags: vec![],
+      query: Query::default(),
+      results: vec![],
       selected: None,
-    }
+    };
+    selector.set_user_library(&ctx.props().user_library);
+    selector
+  }
+
+  fn changed(&mut self, ctx: &Context<Self>) -> bool {
+    self.set_user_library(&ctx.props().user_library);
+    true
   }
 
   fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
@@ -36,42 +160,171 @@ impl Component for PatternSelector {
         true
       }
       Msg::Apply => {
-        if let Some(selected) = self.selected {
+        let selected = self
+          .selected
+          .filter(|&selected| !self.terms[selected].cells.is_empty());
+        if let Some(selected) = selected {
           let on_apply_pattern = ctx.props().on_apply_pattern.clone();
-          let selected_term = self.lexicon.terms[selected].clone();
+          let selected_term = self.terms[selected].clone();
           on_apply_pattern.emit(selected_term);
         }
         true
       }
+      Msg::ChangeText(text) => {
+        self.query.text = text;
+        self.update_results();
+        true
+      }
+      Msg::AddTag(tag) => {
+        if !self.query.tags.contains(&tag) {
+          self.query.tags.push(tag);
+          self.update_results();
+        }
+        true
+      }
+      Msg::RemoveTag(tag) => {
+        self.query.tags.retain(|t| *t != tag);
+        self.update_results();
+        true
+      }
+      Msg::ChangeSort(sort) => {
+        self.query.sort = sort;
+        self.update_results();
+        true
+      }
+      Msg::ChangeSource(source) => {
+        self.source = source;
+        self.update_results();
+        true
+      }
     }
   }
 
   fn view(&self, ctx: &Context<Self>) -> yew::virtual_dom::VNode {
-    let on_change_selected = ctx.link().callback(|event: Event| {
+    let on_input_text = ctx.link().callback(|event: InputEvent| {
       let input = event
+        .target()
+        .and_then(|t| t.dyn_into::<HtmlInputElement>().ok())
+        .unwrap();
+      Msg::ChangeText(input.value())
+    });
+
+    let on_change_sort = ctx.link().callback(|event: Event| {
+      let select = event
+        .target()
+        .and_then(|t| t.dyn_into::<HtmlSelectElement>().ok())
+        .unwrap();
+      Msg::ChangeSort(match select.value().as_str() {
+        "name" => SortOrder::Name,
+        "population" => SortOrder::Population,
+        "size" => SortOrder::Size,
+        _ => SortOrder::Relevance,
+      })
+    });
+
+    let on_add_tag = ctx.link().callback(|event: Event| {
+      let select = event
         .target()
         .and_then(|t| t.dyn_into::<HtmlSelectElement>().ok())
         .unwrap();
-      let selected: usize = input.value().parse().unwrap();
-      Msg::PatternChanged(selected)
+      let tag = select.value();
+      select.set_selected_index(0);
+      Msg::AddTag(tag)
     });
 
+    let selected_term = self.selected.map(|selected| &self.terms[selected]);
+    let can_apply = selected_term.is_some_and(|term| !term.cells.is_empty());
+
+    let on_change_source = {
+      let folders = ctx.props().user_library.folders();
+      ctx.link().callback(move |event: Event| {
+        let select = event
+          .target()
+          .and_then(|t| t.dyn_into::<HtmlSelectElement>().ok())
+          .unwrap();
+        Msg::ChangeSource(match select.value().as_str() {
+          "lexicon" => Source::Lexicon,
+          "user" => Source::User,
+          value => match value.parse::<usize>() {
+            Ok(i) if i < folders.len() => Source::Folder(folders[i].clone()),
+            _ => Source::All,
+          },
+        })
+      })
+    };
+
+    let sort_option = |value: &str, label: &str, sort: SortOrder| {
+      html! {
+        <option value={value.to_string()} selected={self.query.sort == sort}>{label.to_string()}</option>
+      }
+    };
+
     html! {
       <div class="pattern-selector">
-        <select onchange={on_change_selected}>
-          <option disabled={true} selected={self.selected.is_none()}>{"Select a pattern…"}</option>
-          {for {
-            self.lexicon.terms.iter().enumerate()
-              .filter(|(_, term)| !term.cells.is_empty())
-              .map(|(i, term)| html! {
-                <option
-                  value={i.to_string()}
-                  selected={self.selected == Some(i)}
-                >{format_term_option(&term)}</option>
-              })
-          }}
-        </select>
-        <button disabled={self.selected.is_none()} onclick={ctx.link().callback(move |_| Msg::Apply)}>{"Apply"}</button>
+        <div class="search-bar">
+          <input
+            type="search"
+            placeholder="Search patterns…"
+            value={self.query.text.clone()}
+            oninput={on_input_text}
+          />
+          <select onchange={on_change_source} title="Patterns to show">
+            <option value="all" selected={self.source == Source::All}>{"All"}</option>
+            <option value="lexicon" selected={self.source == Source::Lexicon}>{"Lexicon"}</option>
+            <option value="user" selected={self.source == Source::User}>{"My patterns"}</option>
+            {for ctx.props().user_library.folders().into_iter().enumerate().map(|(i, folder)| html! {
+              <option
+                value={i.to_string()}
+                selected={self.source == Source::Folder(folder.clone())}
+              >{format!("My patterns / {}", folder)}</option>
+            })}
+          </select>
+          <select onchange={on_change_sort} title="Sort by">
+            {sort_option("relevance", "Relevance", SortOrder::Relevance)}
+            {sort_option("name", "Name", SortOrder::Name)}
+            {sort_option("population", "Population", SortOrder::Population)}
+            {sort_option("size", "Size", SortOrder::Size)}
+          </select>
+        </div>
+        <div class="tag-filters">
+          {for self.query.tags.iter().map(|tag| {
+            let removed_tag = tag.clone();
+            html! {
+              <span class="tag" title="Remove filter" onclick={ctx.link().callback(move |_| Msg::RemoveTag(removed_tag.clone()))}>
+                {format!("{} ×", tag)}
+              </span>
+            }
+          })}
+          <select onchange={on_add_tag}>
+            <option disabled={true} selected={true}>{"Filter by tag…"}</option>
+            {for self.tags.iter().filter(|tag| !self.query.tags.contains(tag)).map(|tag| html! {
+              <option value={tag.clone()}>{tag.clone()}</option>
+            })}
+          </select>
+        </div>
+        <ul class="results">
+          {for self.results.iter().take(MAX_DISPLAYED_RESULTS).map(|&i| {
+            let term = &self.terms[i];
+            html! {
+              <li
+                class={classes!(
+                  (self.selected == Some(i)).then_some("selected"),
+                  (i >= self.num_lexicon_terms).then_some("user-pattern"),
+                )}
+                onclick={ctx.link().callback(move |_| Msg::PatternChanged(i))}
+                ondblclick={ctx.link().batch_callback(move |_| vec![Msg::PatternChanged(i), Msg::Apply])}
+              >{format_term_option(term)}</li>
+            }
+          })}
+        </ul>
+        {match selected_term {
+          Some(term) => self.view_details(ctx, term),
+          None => html! {},
+        }}
+        <div class="results-footer">
+          <span class="result-count">{format_result_count(self.results.len())}</span>
+          <button disabled={!can_apply} onclick={ctx.link().callback(move |_| Msg::Apply)}>{"Apply"}</button>
+        </div>
       </div>
     }
   }
@@ -88,3 +341,14 @@ fn format_term_option(term: &Term) -> String {
     }
   )
 }
+
+fn format_result_count(count: usize) -> String {
+  match count {
+    0 => "No pattern found".to_string(),
+    1 => "1 pattern".to_string(),
+    _ if count > MAX_DISPLAYED_RESULTS => {
+      format!("{} patterns (first {} shown)", count, MAX_DISPLAYED_RESULTS)
+    }
+    _ => format!("{} patterns", count),
+  }
+}
@@ -0,0 +1,220 @@
+use crate::lexicon::{Cell, Term};
+use crate::library::term_from_cells;
+use crate::life::{bounding_box, CellSet};
+use crate::predecessor::worker::{
+  Input, PredecessorWorker, Progress, Start, PREDECESSOR_WORKER_PATH,
+};
+use crate::predecessor::{region_cells, Predecessor, MAX_REGION_CELLS};
+use gloo_worker::{Spawnable, WorkerBridge};
+use yew::prelude::*;
+
+/// Searches in a worker for a parent of the selection, or of the whole board,
+/// shown on the board, or proves that it is a Garden of Eden.
+pub struct PredecessorPanel {
+  worker: WorkerBridge<PredecessorWorker>,
+  isolated: bool,
+  /// Cells and region searched, to forget the result when they change.
+  searched: Option<(CellSet, (Cell, Cell))>,
+  running: bool,
+  conflicts: u64,
+  predecessor: Option<Predecessor>,
+}
+
+#[derive(Properties, PartialEq)]
+pub struct Props {
+  pub cells: CellSet,
+  pub selection: Option<(Cell, Cell)>,
+  /// Called with the parent found, to be shown on the board, or none.
+  pub on_show_parent: Callback<Option<CellSet>>,
+  pub on_apply_pattern: Callback<Term>,
+}
+
+pub enum Msg {
+  ToggleIsolated,
+  Start,
+  Stop,
+  ReceiveProgress(Progress),
+  LoadParent,
+}
+
+impl PredecessorPanel {
+  /// Region searched: the selection, or else the bounding box of the board.
+  fn region(ctx: &Context<Self>) -> Option<(Cell, Cell)> {
+    ctx
+      .props()
+      .selection
+      .or_else(|| bounding_box(&ctx.props().cells))
+  }
+
+  fn view_result(&self, ctx: &Context<Self>) -> Html {
+    match &self.predecessor {
+      Some(Predecessor::Parent(parent)) => html! {
+        <>
+          <div class="predecessor-result">
+            {format!("Found a parent of {} cells, outlined on the board.", parent.len())}
+          </div>
+          <button onclick={ctx.link().callback(|_| Msg::LoadParent)}>{"Load the parent"}</button>
+        </>
+      },
+      Some(Predecessor::GardenOfEden) => html! {
+        <div class="predecessor-result">
+          {"No parent, whatever is around: this is a Garden of Eden."}
+        </div>
+      },
+      Some(Predecessor::NoneInRegion) => html! {
+        <div class="predecessor-result">
+          {"No parent within a cell of the region gives exactly this pattern."}
+        </div>
+      },
+      None if self.running => html! {
+        <div class="predecessor-result">{format!("Searching… {} conflicts", self.conflicts)}</div>
+      },
+      None => html! {},
+    }
+  }
+}
+
+impl Component for PredecessorPanel {
+  type Message = Msg;
+  type Properties = Props;
+
+  fn create(ctx: &Context<Self>) -> Self {
+    let link = ctx.link().clone();
+    let worker = PredecessorWorker::spawner()
+      .callback(move |progress| link.send_message(Msg::ReceiveProgress(progress)))
+      .spawn(PREDECESSOR_WORKER_PATH);
+    Self {
+      worker,
+      isolated: true,
+      searched: None,
+      running: false,
+      conflicts: 0,
+      predecessor: None,
+    }
+  }
+
+  fn changed(&mut self, ctx: &Context<Self>) -> bool {
+    // The result is about other cells, e.g. after the next generation.
+    let outdated = match &self.searched {
+      Some((cells, region)) => *cells != ctx.props().cells || Some(*region) != Self::region(ctx),
+      None => false,
+    };
+    if outdated {
+      if self.running {
+        self.worker.send(Input::Stop);
+        self.running = false;
+      }
+      if let Some(Predecessor::Parent(_)) = self.predecessor {
+        ctx.props().on_show_parent.emit(None);
+      }
+      self.searched = None;
+      self.predecessor = None;
+    }
+    true
+  }
+
+  fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+    match msg {
+      Msg::ToggleIsolated => self.isolated = !self.isolated,
+      Msg::Start => {
+        if let Some(region) = Self::region(ctx) {
+          let target = ctx.props().cells.clone();
+          self.worker.send(Input::Start(Start {
+            target: target.clone(),
+            region,
+            isolated: self.isolated,
+          }));
+          self.searched = Some((target, region));
+          self.running = true;
+          self.conflicts = 0;
+          self.predecessor = None;
+          ctx.props().on_show_parent.emit(None);
+        }
+      }
+      Msg::Stop => {
+        self.worker.send(Input::Stop);
+        self.running = false;
+      }
+      Msg::ReceiveProgress(progress) => {
+        // Progress of a search stopped since, or of another target.
+        if !self.running {
+          return false;
+        }
+        self.conflicts = progress.conflicts;
+        if let Some(predecessor) = progress.predecessor {
+          if let Predecessor::Parent(parent) = &predecessor {
+            ctx.props().on_show_parent.emit(Some(parent.clone()));
+          }
+          self.running = false;
+          self.predecessor = Some(predecessor);
+        }
+      }
+      Msg::LoadParent => {
+        if let Some(Predecessor::Parent(parent)) = &self.predecessor {
+          let term = term_from_cells(
+            "Parent".to_string(),
+            "Predecessor found by the predecessor search".to_string(),
+            vec!["predecessor".to_string()],
+            parent,
+            None,
+          );
+          ctx.props().on_apply_pattern.emit(term);
+        }
+      }
+    }
+    true
+  }
+
+  fn view(&self, ctx: &Context<Self>) -> Html {
+    let region = Self::region(ctx);
+    let hint = match region {
+      None => Some("Draw a pattern or select a region first.".to_string()),
+      Some(region) if region_cells(region) > MAX_REGION_CELLS => Some(format!(
+        "The region is too big: select at most {} cells.",
+        MAX_REGION_CELLS
+      )),
+      Some(_) => None,
+    };
+    let subject = if ctx.props().selection.is_some() {
+      "the selection"
+    } else {
+      "the board"
+    };
+
+    html! {
+      <details class="predecessor-panel">
+        <summary>{"Predecessor search"}</summary>
+        <div class="hint">
+          {format!("Searches for a parent of {}, with cells within one cell of it.", subject)}
+        </div>
+        <label>
+          <input
+            type="checkbox"
+            checked={self.isolated}
+            disabled={self.running}
+            onchange={ctx.link().callback(|_| Msg::ToggleIsolated)}
+          />
+          {"Nothing else around"}
+        </label>
+        <div class="hint">
+          {if self.isolated {
+            "The parent must give exactly this pattern."
+          } else {
+            "Anything may appear around: with no parent, it is a Garden of Eden."
+          }}
+        </div>
+        {if self.running {
+          html! { <button onclick={ctx.link().callback(|_| Msg::Stop)}>{"Stop"}</button> }
+        } else {
+          html! {
+            <button disabled={hint.is_some()} onclick={ctx.link().callback(|_| Msg::Start)}>
+              {"Find a parent"}
+            </button>
+          }
+        }}
+        {for hint.map(|hint| html! { <div class="hint">{hint}</div> })}
+        {self.view_result(ctx)}
+      </details>
+    }
+  }
+}
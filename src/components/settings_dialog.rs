@@ -0,0 +1,95 @@
+use crate::settings::{default_settings, NumericSetting, Settings};
+use wasm_bindgen::JsCast;
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+/// Dialog editing the display settings, opened from the panel or with ",",
+/// and closed with Escape. Changes apply right away and are saved with the
+/// other settings.
+pub struct SettingsDialog;
+
+#[derive(Properties, PartialEq)]
+pub struct Props {
+  pub settings: Settings,
+  pub on_change: Callback<Settings>,
+  pub on_close: Callback<()>,
+}
+
+pub enum Msg {
+  Change(NumericSetting, f64),
+  /// Back to the default display settings, keeping the key bindings and the
+  /// theme, which have their own panels.
+  Reset,
+  Close,
+}
+
+impl Component for SettingsDialog {
+  type Message = Msg;
+  type Properties = Props;
+
+  fn create(_: &Context<Self>) -> Self {
+    Self
+  }
+
+  fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+    let settings = &ctx.props().settings;
+    match msg {
+      Msg::Change(setting, value) => {
+        ctx.props().on_change.emit(setting.set(settings, value));
+      }
+      Msg::Reset => {
+        ctx.props().on_change.emit(Settings {
+          key_bindings: settings.key_bindings.clone(),
+          theme: settings.theme,
+          ..default_settings()
+        });
+      }
+      Msg::Close => ctx.props().on_close.emit(()),
+    }
+    false
+  }
+
+  fn view(&self, ctx: &Context<Self>) -> Html {
+    let settings = &ctx.props().settings;
+
+    html! {
+      <>
+        <div class="settings-dialog-backdrop" onclick={ctx.link().callback(|_| Msg::Close)} />
+        <div class="settings-dialog">
+          <h2>{"Settings"}</h2>
+          <ul>
+            {for NumericSetting::ALL.iter().map(|&setting| {
+              let (min, max, step) = setting.range();
+              let on_change = ctx.link().batch_callback(move |event: Event| {
+                let input = event
+                  .target()
+                  .and_then(|t| t.dyn_into::<HtmlInputElement>().ok())
+                  .unwrap();
+                input.value().parse::<f64>().ok().map(|value| Msg::Change(setting, value))
+              });
+              html! {
+                <li>
+                  <label>
+                    <span>{setting.label()}</span>
+                    <input
+                      type="number"
+                      min={min.to_string()}
+                      max={max.to_string()}
+                      step={step.to_string()}
+                      value={setting.get(settings).to_string()}
+                      onchange={on_change}
+                    />
+                  </label>
+                </li>
+              }
+            })}
+          </ul>
+          <div class="buttons">
+            <button onclick={ctx.link().callback(|_| Msg::Reset)}>{"Reset to defaults"}</button>
+            <button onclick={ctx.link().callback(|_| Msg::Close)}>{"Close"}</button>
+          </div>
+        </div>
+      </>
+    }
+  }
+}
@@ -0,0 +1,163 @@
+use crate::lexicon::Term;
+use crate::library::term_from_cells;
+use crate::soup::{soup, SoupOptions, Symmetry};
+use wasm_bindgen::JsCast;
+use web_sys::{HtmlInputElement, HtmlSelectElement};
+use yew::prelude::*;
+
+/// Characters of the random seeds.
+const SEED_ALPHABET: &[u8] = b"abcdefghijklmnopqrstuvwxyz0123456789";
+const SEED_LENGTH: usize = 12;
+const MAX_SIZE: u32 = 256;
+
+fn random_seed() -> String {
+  (0..SEED_LENGTH)
+    .map(|_| {
+      let i = (js_sys::Math::random() * SEED_ALPHABET.len() as f64) as usize;
+      SEED_ALPHABET[i.min(SEED_ALPHABET.len() - 1)] as char
+    })
+    .collect()
+}
+
+/// Fills the board with a random soup. The seed is shown and can be edited, so
+/// that a soup can be shared and made again.
+pub struct SoupPanel {
+  options: SoupOptions,
+  seed: String,
+}
+
+#[derive(Properties, PartialEq)]
+pub struct Props {
+  pub on_apply_pattern: Callback<Term>,
+}
+
+pub enum Msg {
+  ChangeSize(u32),
+  ChangeDensity(f64),
+  ChangeSymmetry(Symmetry),
+  ChangeSeed(String),
+  Generate,
+  /// Generates a soup from a new random seed.
+  Next,
+}
+
+fn input_of(event: &Event) -> HtmlInputElement {
+  event
+    .target()
+    .and_then(|t| t.dyn_into::<HtmlInputElement>().ok())
+    .unwrap()
+}
+
+impl SoupPanel {
+  fn generate(&self, ctx: &Context<Self>) {
+    let options = &self.options;
+    let term = term_from_cells(
+      format!("Soup {}", self.seed),
+      format!(
+        "{0}×{0} soup at {1}% density with {2} symmetry, seed {3}",
+        options.size,
+        (options.density * 100.0).round(),
+        options.symmetry.label(),
+        self.seed
+      ),
+      vec!["soup".to_string()],
+      &soup(&self.seed, options),
+      None,
+    );
+    ctx.props().on_apply_pattern.emit(term);
+  }
+}
+
+impl Component for SoupPanel {
+  type Message = Msg;
+  type Properties = Props;
+
+  fn create(_: &Context<Self>) -> Self {
+    Self {
+      options: SoupOptions::default(),
+      seed: random_seed(),
+    }
+  }
+
+  fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+    match msg {
+      Msg::ChangeSize(size) => self.options.size = size.clamp(1, MAX_SIZE),
+      Msg::ChangeDensity(density) => self.options.density = density.clamp(0.0, 1.0),
+      Msg::ChangeSymmetry(symmetry) => self.options.symmetry = symmetry,
+      Msg::ChangeSeed(seed) => self.seed = seed,
+      Msg::Generate => self.generate(ctx),
+      Msg::Next => {
+        self.seed = random_seed();
+        self.generate(ctx);
+      }
+    }
+    true
+  }
+
+  fn view(&self, ctx: &Context<Self>) -> Html {
+    let on_change_size = ctx
+      .link()
+      .batch_callback(|event: Event| input_of(&event).value().parse().ok().map(Msg::ChangeSize));
+    let on_change_density = ctx.link().batch_callback(|event: Event| {
+      let percent: f64 = input_of(&event).value().parse().ok()?;
+      Some(Msg::ChangeDensity(percent / 100.0))
+    });
+    let on_change_symmetry = ctx.link().callback(|event: Event| {
+      let select = event
+        .target()
+        .and_then(|t| t.dyn_into::<HtmlSelectElement>().ok())
+        .unwrap();
+      let index: usize = select.value().parse().unwrap();
+      Msg::ChangeSymmetry(Symmetry::ALL[index])
+    });
+    let on_input_seed = ctx.link().callback(|event: InputEvent| {
+      let input = event
+        .target()
+        .and_then(|t| t.dyn_into::<HtmlInputElement>().ok())
+        .unwrap();
+      Msg::ChangeSeed(input.value())
+    });
+    let density = (self.options.density * 100.0).round();
+
+    html! {
+      <details class="soup-panel">
+        <summary>{"Random soup"}</summary>
+        <div class="soup-options">
+          <label>
+            <span>{"Size"}</span>
+            <input
+              type="number" min="1" max={MAX_SIZE.to_string()}
+              value={self.options.size.to_string()}
+              onchange={on_change_size}
+            />
+          </label>
+          <label>
+            <span>{"Density"}</span>
+            <input
+              type="range" min="0" max="100"
+              value={density.to_string()}
+              onchange={on_change_density}
+            />
+            <span class="unit">{format!("{}%", density)}</span>
+          </label>
+          <label>
+            <span>{"Symmetry"}</span>
+            <select onchange={on_change_symmetry}>
+              {for Symmetry::ALL.iter().enumerate().map(|(i, &symmetry)| html! {
+                <option value={i.to_string()} selected={symmetry == self.options.symmetry}>
+                  {symmetry.label()}
+                </option>
+              })}
+            </select>
+          </label>
+          <label>
+            <span>{"Seed"}</span>
+            <input type="text" value={self.seed.clone()} oninput={on_input_seed} />
+          </label>
+        </div>
+        <button onclick={ctx.link().callback(|_| Msg::Generate)}>{"Generate"}</button>
+        <button onclick={ctx.link().callback(|_| Msg::Next)}>{"Next soup"}</button>
+      </details>
+    }
+  }
+}
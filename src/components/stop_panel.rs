@@ -0,0 +1,209 @@
+use crate::lexicon::Cell;
+use crate::stop::StopConditions;
+use wasm_bindgen::JsCast;
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+/// Default values of the conditions taking a number, when they are checked.
+const DEFAULT_GENERATION: u32 = 1000;
+const DEFAULT_STABLE_FOR: u32 = 100;
+
+/// Edits the conditions pausing the simulation on their own. The region and
+/// the watched area are taken from the selection (Shift + drag).
+pub struct StopPanel;
+
+#[derive(Properties, PartialEq)]
+pub struct Props {
+  pub conditions: StopConditions,
+  pub selection: Option<(Cell, Cell)>,
+  pub on_change: Callback<StopConditions>,
+}
+
+pub enum Msg {
+  Change(StopConditions),
+}
+
+fn input_of(event: &Event) -> HtmlInputElement {
+  event
+    .target()
+    .and_then(|t| t.dyn_into::<HtmlInputElement>().ok())
+    .unwrap()
+}
+
+fn describe((min, max): (Cell, Cell)) -> String {
+  format!("({}, {}) to ({}, {})", min.x, min.y, max.x, max.y)
+}
+
+impl StopPanel {
+  /// Checkbox and number input of a condition taking a number, e.g. the
+  /// generation to stop at.
+  fn number_condition(
+    &self,
+    ctx: &Context<Self>,
+    label: &str,
+    value: Option<u32>,
+    default: u32,
+    set: fn(&mut StopConditions, Option<u32>),
+  ) -> Html {
+    let conditions = ctx.props().conditions.clone();
+    let on_toggle = ctx.link().callback({
+      let conditions = conditions.clone();
+      move |event: Event| {
+        let mut conditions = conditions.clone();
+        set(
+          &mut conditions,
+          input_of(&event)
+            .checked()
+            .then_some(value.unwrap_or(default)),
+        );
+        Msg::Change(conditions)
+      }
+    });
+    let on_change = ctx.link().batch_callback(move |event: Event| {
+      let value = input_of(&event).value().parse().ok()?;
+      let mut conditions = conditions.clone();
+      set(&mut conditions, Some(value));
+      Some(Msg::Change(conditions))
+    });
+    html! {
+      <li>
+        <label class="checkbox">
+          <input type="checkbox" checked={value.is_some()} onchange={on_toggle} />
+          {label}
+        </label>
+        <input
+          type="number" min="1"
+          disabled={value.is_none()}
+          value={value.unwrap_or(default).to_string()}
+          onchange={on_change}
+        />
+      </li>
+    }
+  }
+
+  fn flag_condition(
+    &self,
+    ctx: &Context<Self>,
+    label: &str,
+    value: bool,
+    set: fn(&mut StopConditions, bool),
+  ) -> Html {
+    let conditions = ctx.props().conditions.clone();
+    let on_toggle = ctx.link().callback(move |event: Event| {
+      let mut conditions = conditions.clone();
+      set(&mut conditions, input_of(&event).checked());
+      Msg::Change(conditions)
+    });
+    html! {
+      <li>
+        <label class="checkbox">
+          <input type="checkbox" checked={value} onchange={on_toggle} />
+          {label}
+        </label>
+      </li>
+    }
+  }
+
+  /// Region of a condition, set to the selection or cleared.
+  fn region_condition(
+    &self,
+    ctx: &Context<Self>,
+    label: &str,
+    value: Option<(Cell, Cell)>,
+    set: fn(&mut StopConditions, Option<(Cell, Cell)>),
+  ) -> Html {
+    let selection = ctx.props().selection;
+    let change = |region: Option<(Cell, Cell)>| {
+      let conditions = ctx.props().conditions.clone();
+      ctx.link().callback(move |_| {
+        let mut conditions = conditions.clone();
+        set(&mut conditions, region);
+        Msg::Change(conditions)
+      })
+    };
+    html! {
+      <li>
+        <span>{label}</span>
+        <span class="unit">{value.map(describe).unwrap_or_else(|| "None".to_string())}</span>
+        <button
+          disabled={selection.is_none()}
+          title="Use the region selected with Shift + drag"
+          onclick={change(selection)}
+        >
+          {"Use selection"}
+        </button>
+        {if value.is_some() {
+          html! { <button onclick={change(None)}>{"Clear"}</button> }
+        } else {
+          html! {}
+        }}
+      </li>
+    }
+  }
+}
+
+impl Component for StopPanel {
+  type Message = Msg;
+  type Properties = Props;
+
+  fn create(_: &Context<Self>) -> Self {
+    Self
+  }
+
+  fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+    match msg {
+      Msg::Change(conditions) => ctx.props().on_change.emit(conditions),
+    }
+    false
+  }
+
+  fn view(&self, ctx: &Context<Self>) -> Html {
+    let conditions = &ctx.props().conditions;
+
+    html! {
+      <details class="stop-panel">
+        <summary>{"Stop conditions"}</summary>
+        <ul>
+          {self.number_condition(
+            ctx,
+            "At generation",
+            conditions.at_generation,
+            DEFAULT_GENERATION,
+            |conditions, value| conditions.at_generation = value,
+          )}
+          {self.flag_condition(
+            ctx,
+            "When the population dies out",
+            conditions.extinct,
+            |conditions, value| conditions.extinct = value,
+          )}
+          {self.number_condition(
+            ctx,
+            "When the population is stable for (generations)",
+            conditions.stable_for,
+            DEFAULT_STABLE_FOR,
+            |conditions, value| conditions.stable_for = value,
+          )}
+          {self.flag_condition(
+            ctx,
+            "When a previous state comes back",
+            conditions.repeated,
+            |conditions, value| conditions.repeated = value,
+          )}
+          {self.region_condition(
+            ctx,
+            "When the pattern leaves",
+            conditions.region,
+            |conditions, value| conditions.region = value,
+          )}
+          {self.region_condition(
+            ctx,
+            "When a cell changes in",
+            conditions.watched_area,
+            |conditions, value| conditions.watched_area = value,
+          )}
+        </ul>
+      </details>
+    }
+  }
+}
@@ -0,0 +1,92 @@
+use crate::color_utils::{from_hex, to_hex, Rgb};
+use crate::theme::{Preset, Theme, ThemeChoice, ThemeColor};
+use wasm_bindgen::JsCast;
+use web_sys::{HtmlInputElement, HtmlSelectElement};
+use yew::prelude::*;
+
+/// Picks the theme among presets, or edits its colors one by one. Editing a
+/// color of a preset makes a custom theme out of it.
+pub struct ThemePanel;
+
+#[derive(Properties, PartialEq)]
+pub struct Props {
+  pub choice: ThemeChoice,
+  /// Theme currently used, the one edited.
+  pub theme: Theme,
+  pub on_change: Callback<ThemeChoice>,
+}
+
+pub enum Msg {
+  /// A preset, or `None` to follow the system.
+  ChoosePreset(Option<Preset>),
+  ChangeColor(ThemeColor, Rgb),
+}
+
+impl Component for ThemePanel {
+  type Message = Msg;
+  type Properties = Props;
+
+  fn create(_: &Context<Self>) -> Self {
+    Self
+  }
+
+  fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+    let choice = match msg {
+      Msg::ChoosePreset(Some(preset)) => ThemeChoice::Custom(preset.theme()),
+      Msg::ChoosePreset(None) => ThemeChoice::System,
+      Msg::ChangeColor(color, value) => {
+        ThemeChoice::Custom(ctx.props().theme.with_color(color, value))
+      }
+    };
+    ctx.props().on_change.emit(choice);
+    false
+  }
+
+  fn view(&self, ctx: &Context<Self>) -> Html {
+    let on_choose_preset = ctx.link().callback(|event: Event| {
+      let select = event
+        .target()
+        .and_then(|t| t.dyn_into::<HtmlSelectElement>().ok())
+        .unwrap();
+      let index = select.value().parse::<usize>().ok();
+      Msg::ChoosePreset(index.map(|i| Preset::ALL[i]))
+    });
+    let preset = match ctx.props().choice {
+      ThemeChoice::System => None,
+      ThemeChoice::Custom(theme) => Some(Preset::matching(&theme)),
+    };
+    let theme = ctx.props().theme;
+
+    html! {
+      <details class="theme-panel">
+        <summary>{"Theme"}</summary>
+        <select onchange={on_choose_preset}>
+          <option value="system" selected={preset.is_none()}>{"Same as the system"}</option>
+          {for Preset::ALL.iter().enumerate().map(|(i, &p)| html! {
+            <option value={i.to_string()} selected={preset == Some(Some(p))}>{p.label()}</option>
+          })}
+          <option value="custom" disabled=true selected={preset == Some(None)}>{"Custom"}</option>
+        </select>
+        <ul class="theme-colors">
+          {for ThemeColor::ALL.iter().map(|&color| {
+            let on_input = ctx.link().batch_callback(move |event: InputEvent| {
+              let input = event
+                .target()
+                .and_then(|t| t.dyn_into::<HtmlInputElement>().ok())
+                .unwrap();
+              from_hex(&input.value()).map(|value| Msg::ChangeColor(color, value))
+            });
+            html! {
+              <li>
+                <label>
+                  <input type="color" value={to_hex(theme.color(color))} oninput={on_input} />
+                  {color.label()}
+                </label>
+              </li>
+            }
+          })}
+        </ul>
+      </details>
+    }
+  }
+}
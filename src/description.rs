@@ -0,0 +1,57 @@
+/// Piece of a lexicon description: either plain text or a cross-reference
+/// to another term, written `{name}` in the lexicon.
+#[derive(Debug, PartialEq)]
+pub enum Fragment {
+  Text(String),
+  Link(String),
+}
+
+pub fn fragments(description: &str) -> Vec<Fragment> {
+  let mut fragments = vec![];
+  let mut rest = description;
+  while let Some(start) = rest.find('{') {
+    let end = match rest[start..].find('}') {
+      Some(end) => start + end,
+      None => break,
+    };
+    if start > 0 {
+      fragments.push(Fragment::Text(rest[..start].to_string()));
+    }
+    fragments.push(Fragment::Link(rest[start + 1..end].to_string()));
+    rest = &rest[end + 1..];
+  }
+  if !rest.is_empty() {
+    fragments.push(Fragment::Text(rest.to_string()));
+  }
+  fragments
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn fragments_splits_links_from_text() {
+    assert_eq!(
+      fragments("One of the {oscillator}s, see {cuphook}."),
+      vec![
+        Fragment::Text("One of the ".to_string()),
+        Fragment::Link("oscillator".to_string()),
+        Fragment::Text("s, see ".to_string()),
+        Fragment::Link("cuphook".to_string()),
+        Fragment::Text(".".to_string()),
+      ]
+    )
+  }
+
+  #[test]
+  fn fragments_keeps_unclosed_braces_as_text() {
+    assert_eq!(
+      fragments("{block} and {oops"),
+      vec![
+        Fragment::Link("block".to_string()),
+        Fragment::Text(" and {oops".to_string()),
+      ]
+    )
+  }
+}
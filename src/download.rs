@@ -0,0 +1,20 @@
+use gloo::file::Blob;
+use wasm_bindgen::JsCast;
+
+/// Makes the browser download `content` as a file named `file_name`.
+pub fn download_file(content: &[u8], mime_type: &str, file_name: &str) {
+  let blob = Blob::new_with_options(content, Some(mime_type));
+  let url = web_sys::Url::create_object_url_with_blob(blob.as_ref()).unwrap();
+  let anchor = web_sys::window()
+    .unwrap()
+    .document()
+    .unwrap()
+    .create_element("a")
+    .unwrap()
+    .dyn_into::<web_sys::HtmlAnchorElement>()
+    .unwrap();
+  anchor.set_href(&url);
+  anchor.set_download(file_name);
+  anchor.click();
+  web_sys::Url::revoke_object_url(&url).unwrap();
+}
@@ -0,0 +1,230 @@
+//! Simulation running in a Web Worker, so that computing the generations of
+//! big patterns doesn't freeze the page.
+//!
+//! The page loads cells into the engine, then asks it to play or to step. At a
+//! target speed, the engine times the generations itself; with a step
+//! exponent, the page asks for the generations of each animation frame with
+//! `Advance`. The engine sends generations back one at a time: while the page
+//! hasn't shown the last one it sent, it keeps computing but only sends the
+//! newest generation once the page is ready, dropping the ones in between. The
+//! stop conditions are checked at every generation though, not only the ones
+//! sent.
+
+use crate::life::{tick, CellSet};
+use crate::speed::{Speed, FRAMES_PER_SECOND};
+use crate::stop::{StopChecker, StopConditions, StopReason};
+use gloo::timers::callback::Interval;
+use gloo_worker::{HandlerId, Worker, WorkerScope};
+use serde::{Deserialize, Serialize};
+
+/// Path of the script of the worker, built by Trunk from `src/bin/worker.rs`.
+pub const WORKER_PATH: &str = "/worker.js";
+
+pub struct Engine {
+  cells: CellSet,
+  tick: u32,
+  epoch: u32,
+  /// Whether the engine is playing, so that it checks the stop conditions.
+  playing: bool,
+  /// Timer computing the generations, while playing at a target speed.
+  interval: Option<Interval>,
+  /// Generations per second while playing at a target speed.
+  gens_per_second: f64,
+  /// Time the engine started playing at the current speed, in milliseconds.
+  started: f64,
+  /// Generations computed since `started`.
+  done: u64,
+  stop_checker: StopChecker,
+  /// Page the generations are sent to.
+  page: Option<HandlerId>,
+  /// Whether the page is still showing the last generation sent.
+  waiting: bool,
+  /// Generations computed since the last one sent.
+  unsent: u32,
+}
+
+#[derive(Serialize, Deserialize)]
+pub enum Input {
+  /// Replaces the cells, e.g. after they were drawn or a pattern was loaded.
+  /// Generations sent afterwards carry the same `epoch`, so that the page can
+  /// ignore the ones computed from the previous cells.
+  Load {
+    cells: CellSet,
+    tick: u32,
+    epoch: u32,
+  },
+  Play {
+    speed: Speed,
+  },
+  /// Computes generations while playing with a step exponent, asked for at
+  /// each frame.
+  Advance {
+    gens: u32,
+  },
+  Pause,
+  SetStopConditions(StopConditions),
+  /// Computes a single generation.
+  Step,
+  /// The page showed the last generation sent, and is ready for the next one.
+  Shown,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Generation {
+  pub epoch: u32,
+  pub tick: u32,
+  pub cells: CellSet,
+  /// Generations computed but never sent since the previous one, as several
+  /// are computed per frame or the page was too slow to show them.
+  pub skipped: u32,
+  /// Why the engine stopped playing at this generation, if it did.
+  pub stopped: Option<StopReason>,
+}
+
+pub enum Msg {
+  Tick,
+}
+
+impl Engine {
+  /// Milliseconds between two ticks of the interval while playing: a frame,
+  /// or longer when less than a generation is computed per frame.
+  fn interval_millis(&self) -> f64 {
+    (1000.0 / FRAMES_PER_SECOND).max(1000.0 / self.gens_per_second)
+  }
+
+  /// Computes the generations due since the engine started playing. When it
+  /// can't keep up, it computes at most an interval's worth of generations at
+  /// once and slows down, rather than trying to catch up.
+  fn play_due(&mut self, scope: &WorkerScope<Self>) {
+    let now = js_sys::Date::now();
+    let due = ((now - self.started) / 1000.0 * self.gens_per_second) as u64;
+    let max = (self.interval_millis() / 1000.0 * self.gens_per_second).ceil() as u64;
+    let count = due.saturating_sub(self.done).min(max);
+    self.done += count;
+    if due > self.done {
+      self.started = now;
+      self.done = 0;
+    }
+    self.step(scope, count);
+  }
+
+  fn step(&mut self, scope: &WorkerScope<Self>, count: u64) {
+    if count == 0 {
+      return;
+    }
+    for _ in 0..count {
+      self.cells = tick(&self.cells);
+      self.tick += 1;
+      self.unsent += 1;
+      if !self.playing {
+        continue;
+      }
+      if let Some(reason) = self.stop_checker.check(self.tick, &self.cells) {
+        // Sent even if the page is still showing the previous generation, so
+        // that it knows the engine stopped.
+        self.playing = false;
+        self.interval = None;
+        self.send(scope, Some(reason));
+        return;
+      }
+    }
+    if !self.waiting {
+      self.send(scope, None);
+    }
+  }
+
+  fn send(&mut self, scope: &WorkerScope<Self>, stopped: Option<StopReason>) {
+    if let Some(page) = self.page {
+      scope.respond(
+        page,
+        Generation {
+          epoch: self.epoch,
+          tick: self.tick,
+          cells: self.cells.clone(),
+          skipped: self.unsent.saturating_sub(1),
+          stopped,
+        },
+      );
+      self.waiting = true;
+      self.unsent = 0;
+    }
+  }
+}
+
+impl Worker for Engine {
+  type Message = Msg;
+  type Input = Input;
+  type Output = Generation;
+
+  fn create(_: &WorkerScope<Self>) -> Self {
+    Self {
+      cells: CellSet::new(),
+      tick: 0,
+      epoch: 0,
+      playing: false,
+      interval: None,
+      gens_per_second: FRAMES_PER_SECOND,
+      started: 0.0,
+      done: 0,
+      page: None,
+      waiting: false,
+      stop_checker: StopChecker::default(),
+      unsent: 0,
+    }
+  }
+
+  fn update(&mut self, scope: &WorkerScope<Self>, msg: Self::Message) {
+    match msg {
+      Msg::Tick => self.play_due(scope),
+    }
+  }
+
+  fn received(&mut self, scope: &WorkerScope<Self>, msg: Self::Input, id: HandlerId) {
+    self.page = Some(id);
+    match msg {
+      Input::Load { cells, tick, epoch } => {
+        self.cells = cells;
+        self.tick = tick;
+        self.epoch = epoch;
+        self.unsent = 0;
+        self.stop_checker.reset(self.tick, &self.cells);
+      }
+      Input::Play { speed } => {
+        if !self.playing {
+          self.stop_checker.reset(self.tick, &self.cells);
+        }
+        self.playing = true;
+        self.interval = match speed {
+          Speed::StepExponent(_) => None,
+          Speed::Target(gens_per_second) => {
+            self.gens_per_second = gens_per_second;
+            self.started = js_sys::Date::now();
+            self.done = 0;
+            let scope = scope.clone();
+            Some(Interval::new(self.interval_millis() as u32, move || {
+              scope.send_message(Msg::Tick)
+            }))
+          }
+        };
+      }
+      // Ignored once stopped, if the page asked before it knew.
+      Input::Advance { gens } if self.playing => self.step(scope, gens.into()),
+      Input::Advance { .. } => {}
+      Input::Pause => {
+        self.playing = false;
+        self.interval = None;
+      }
+      Input::SetStopConditions(conditions) => {
+        self.stop_checker = StopChecker::new(conditions);
+        self.stop_checker.reset(self.tick, &self.cells);
+      }
+      Input::Step => self.step(scope, 1),
+      Input::Shown => {
+        self.waiting = false;
+        if self.unsent > 0 {
+          self.send(scope, None);
+        }
+      }
+    }
+  }
+}
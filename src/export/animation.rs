@@ -0,0 +1,126 @@
+use super::raster::{image_bounds, rasterize, RgbaImage};
+use super::{ExportError, ExportOptions};
+use crate::color_utils::Rgb;
+use crate::life::{tick, CellSet};
+use std::collections::{HashMap, VecDeque};
+use std::ops::Range;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AnimationFormat {
+  Gif,
+  Apng,
+}
+
+/// Renders the generations in `generations` (0 being `cells` itself) as an
+/// animation, each frame showing the `num_previous` previous generations as a
+/// trail. All frames share the same framing so the pattern doesn't jump around.
+pub fn render_animation(
+  cells: &CellSet,
+  generations: Range<u32>,
+  num_previous: usize,
+  options: &ExportOptions,
+  format: AnimationFormat,
+) -> Result<Vec<u8>, ExportError> {
+  let mut frames: Vec<(CellSet, Vec<CellSet>)> = vec![];
+  let mut current = cells.clone();
+  let mut previous_gens: VecDeque<CellSet> = VecDeque::new();
+  for generation in 0..generations.end {
+    if generation >= generations.start {
+      frames.push((current.clone(), previous_gens.iter().cloned().collect()));
+    }
+    previous_gens.push_front(current.clone());
+    previous_gens.truncate(num_previous);
+    current = tick(&current);
+  }
+
+  let bounds = image_bounds(
+    frames
+      .iter()
+      .flat_map(|(cells, previous_gens)| std::iter::once(cells).chain(previous_gens)),
+    options.margin,
+  );
+  let images = frames
+    .iter()
+    .map(|(cells, previous_gens)| rasterize(cells, previous_gens, bounds, options))
+    .collect::<Result<Vec<_>, _>>()?;
+
+  match format {
+    AnimationFormat::Gif => encode_gif(&images, options.frame_delay),
+    AnimationFormat::Apng => encode_apng(&images, options.frame_delay),
+  }
+}
+
+fn encode_gif(images: &[RgbaImage], frame_delay: u16) -> Result<Vec<u8>, ExportError> {
+  let (width, height) = match images.first() {
+    Some(image) => (image.width, image.height),
+    None => return Ok(vec![]),
+  };
+  if width > u16::MAX as u32 || height > u16::MAX as u32 {
+    return Err(ExportError::TooLarge(width, height));
+  }
+  let (width, height) = (width as u16, height as u16);
+
+  // Boards only use a handful of colours, so an exact palette is usually
+  // possible and avoids the artifacts of quantization.
+  let mut palette: HashMap<Rgb, u8> = HashMap::new();
+  let exact_palette = images
+    .iter()
+    .flat_map(|image| image.pixels.chunks_exact(4))
+    .all(|pixel| {
+      let color = [pixel[0], pixel[1], pixel[2]];
+      if palette.contains_key(&color) {
+        return true;
+      }
+      let len = palette.len();
+      if len == 256 {
+        return false;
+      }
+      palette.insert(color, len as u8);
+      true
+    });
+
+  let mut global_palette = vec![0; palette.len() * 3];
+  for (color, &index) in &palette {
+    global_palette[index as usize * 3..index as usize * 3 + 3].copy_from_slice(color);
+  }
+
+  let mut bytes = vec![];
+  {
+    let mut encoder = gif::Encoder::new(&mut bytes, width, height, &[])?;
+    encoder.set_repeat(gif::Repeat::Infinite)?;
+    for image in images {
+      let mut frame = if exact_palette {
+        let indices: Vec<u8> = image
+          .pixels
+          .chunks_exact(4)
+          .map(|pixel| palette[&[pixel[0], pixel[1], pixel[2]]])
+          .collect();
+        gif::Frame::from_palette_pixels(width, height, &indices, &global_palette, None)
+      } else {
+        gif::Frame::from_rgba_speed(width, height, &mut image.pixels.clone(), 10)
+      };
+      frame.delay = frame_delay / 10;
+      encoder.write_frame(&frame)?;
+    }
+  }
+  Ok(bytes)
+}
+
+fn encode_apng(images: &[RgbaImage], frame_delay: u16) -> Result<Vec<u8>, ExportError> {
+  let (width, height) = match images.first() {
+    Some(image) => (image.width, image.height),
+    None => return Ok(vec![]),
+  };
+  let mut bytes = vec![];
+  let mut encoder = png::Encoder::new(&mut bytes, width, height);
+  encoder.set_color(png::ColorType::Rgba);
+  encoder.set_depth(png::BitDepth::Eight);
+  encoder.set_animated(images.len() as u32, 0)?;
+  encoder.set_frame_delay(frame_delay, 1000)?;
+  let mut writer = encoder.write_header()?;
+  for image in images {
+    writer.write_image_data(&image.pixels)?;
+  }
+  writer.finish()?;
+  Ok(bytes)
+}
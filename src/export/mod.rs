@@ -0,0 +1,97 @@
+//! Headless rendering of boards to image files, used both by the web UI and
+//! natively by the `lifeee-export` binary.
+
+mod animation;
+mod raster;
+mod svg;
+pub mod worker;
+
+use crate::life::CellSet;
+use crate::theme::{Preset, Theme};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+pub use animation::{render_animation, AnimationFormat};
+pub use raster::{image_bounds, rasterize, RgbaImage};
+pub use svg::{render_svg, Area, SvgScope};
+
+/// Images larger than this (in pixels) are refused rather than allocated.
+const MAX_PIXELS: u64 = 64 * 1024 * 1024;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ExportOptions {
+  pub cell_size: u32,
+  pub grid: bool,
+  /// Empty cells kept around the pattern, on each side.
+  pub margin: u32,
+  pub theme: Theme,
+  /// Delay between two frames of an animation, in milliseconds.
+  pub frame_delay: u16,
+}
+
+impl Default for ExportOptions {
+  fn default() -> Self {
+    Self {
+      cell_size: 10,
+      grid: true,
+      margin: 2,
+      theme: Preset::Light.theme(),
+      frame_delay: 100,
+    }
+  }
+}
+
+#[derive(Debug)]
+pub enum ExportError {
+  TooLarge(u32, u32),
+  Png(png::EncodingError),
+  Gif(gif::EncodingError),
+}
+
+impl fmt::Display for ExportError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      ExportError::TooLarge(width, height) => {
+        write!(f, "image would be too large ({}×{} pixels)", width, height)
+      }
+      ExportError::Png(error) => write!(f, "could not encode PNG: {}", error),
+      ExportError::Gif(error) => write!(f, "could not encode GIF: {}", error),
+    }
+  }
+}
+
+impl std::error::Error for ExportError {}
+
+impl From<png::EncodingError> for ExportError {
+  fn from(error: png::EncodingError) -> Self {
+    ExportError::Png(error)
+  }
+}
+
+impl From<gif::EncodingError> for ExportError {
+  fn from(error: gif::EncodingError) -> Self {
+    ExportError::Gif(error)
+  }
+}
+
+/// Renders `cells` to a PNG image, framed on the pattern and its trail.
+pub fn render_png(
+  cells: &CellSet,
+  previous_gens: &[CellSet],
+  options: &ExportOptions,
+) -> Result<Vec<u8>, ExportError> {
+  let bounds = image_bounds(std::iter::once(cells).chain(previous_gens), options.margin);
+  let image = rasterize(cells, previous_gens, bounds, options)?;
+  encode_png(&image)
+}
+
+fn encode_png(image: &RgbaImage) -> Result<Vec<u8>, ExportError> {
+  let mut bytes = vec![];
+  let mut encoder = png::Encoder::new(&mut bytes, image.width, image.height);
+  encoder.set_color(png::ColorType::Rgba);
+  encoder.set_depth(png::BitDepth::Eight);
+  let mut writer = encoder.write_header()?;
+  writer.write_image_data(&image.pixels)?;
+  writer.finish()?;
+  Ok(bytes)
+}
@@ -0,0 +1,232 @@
+use super::{ExportError, ExportOptions, MAX_PIXELS};
+use crate::color_utils::Rgb;
+use crate::lexicon::Cell;
+use crate::life::{bounding_box, CellSet};
+
+pub struct RgbaImage {
+  pub width: u32,
+  pub height: u32,
+  pub pixels: Vec<u8>,
+}
+
+impl RgbaImage {
+  pub fn new(width: u32, height: u32, color: Rgb) -> Self {
+    let pixels = (0..width as usize * height as usize)
+      .flat_map(|_| [color[0], color[1], color[2], 0xff])
+      .collect();
+    Self {
+      width,
+      height,
+      pixels,
+    }
+  }
+
+  pub fn fill_rect(&mut self, x: u32, y: u32, width: u32, height: u32, color: Rgb) {
+    for j in y..u32::min(y + height, self.height) {
+      for i in x..u32::min(x + width, self.width) {
+        let index = 4 * (j as usize * self.width as usize + i as usize);
+        self.pixels[index..index + 3].copy_from_slice(&color);
+      }
+    }
+  }
+
+  pub fn clear(&mut self, color: Rgb) {
+    for pixel in self.pixels.chunks_exact_mut(4) {
+      pixel.copy_from_slice(&[color[0], color[1], color[2], 0xff]);
+    }
+  }
+
+  /// Fills a rectangle whose bounds may fall between pixels, like a canvas
+  /// does: pixels partly covered are blended with `color` in proportion of the
+  /// area covered.
+  pub fn blend_rect(&mut self, x: f64, y: f64, width: f64, height: f64, color: Rgb) {
+    let (x0, y0) = (x.max(0.0), y.max(0.0));
+    let (x1, y1) = (
+      (x + width).min(self.width as f64),
+      (y + height).min(self.height as f64),
+    );
+    if x0 >= x1 || y0 >= y1 {
+      return;
+    }
+    for j in y0.floor() as u32..y1.ceil() as u32 {
+      let coverage_y = y1.min(j as f64 + 1.0) - y0.max(j as f64);
+      for i in x0.floor() as u32..x1.ceil() as u32 {
+        let coverage = coverage_y * (x1.min(i as f64 + 1.0) - x0.max(i as f64));
+        let index = 4 * (j as usize * self.width as usize + i as usize);
+        for (pixel, &channel) in self.pixels[index..index + 3].iter_mut().zip(&color) {
+          let from = *pixel as f64;
+          *pixel = (from + (channel as f64 - from) * coverage).round() as u8;
+        }
+      }
+    }
+  }
+}
+
+/// Smallest area (top-left and bottom-right cells, inclusive) containing all
+/// the given cell sets, extended by `margin` cells on each side.
+pub fn image_bounds<'a>(
+  cell_sets: impl IntoIterator<Item = &'a CellSet>,
+  margin: u32,
+) -> (Cell, Cell) {
+  let (min, max) = cell_sets
+    .into_iter()
+    .filter_map(bounding_box)
+    .reduce(|(min1, max1), (min2, max2)| {
+      (
+        Cell {
+          x: min1.x.min(min2.x),
+          y: min1.y.min(min2.y),
+        },
+        Cell {
+          x: max1.x.max(max2.x),
+          y: max1.y.max(max2.y),
+        },
+      )
+    })
+    .unwrap_or((Cell { x: 0, y: 0 }, Cell { x: 0, y: 0 }));
+  let margin = margin as i32;
+  (
+    Cell {
+      x: min.x - margin,
+      y: min.y - margin,
+    },
+    Cell {
+      x: max.x + margin,
+      y: max.y + margin,
+    },
+  )
+}
+
+/// Draws the area delimited by `bounds` the same way `Board` does: grid first,
+/// then the trail from the oldest generation to the newest, then live cells.
+pub fn rasterize(
+  cells: &CellSet,
+  previous_gens: &[CellSet],
+  (min, max): (Cell, Cell),
+  options: &ExportOptions,
+) -> Result<RgbaImage, ExportError> {
+  let grid_width = if options.grid { 1 } else { 0 };
+  let pitch = options.cell_size + grid_width;
+  let columns = (max.x - min.x + 1) as u64;
+  let rows = (max.y - min.y + 1) as u64;
+  let width = columns * pitch as u64 + grid_width as u64;
+  let height = rows * pitch as u64 + grid_width as u64;
+  if width * height > MAX_PIXELS {
+    return Err(ExportError::TooLarge(
+      width.min(u32::MAX as u64) as u32,
+      height.min(u32::MAX as u64) as u32,
+    ));
+  }
+  let (width, height) = (width as u32, height as u32);
+
+  let mut image = RgbaImage::new(width, height, options.theme.background);
+  if options.grid {
+    for i in 0..=columns as u32 {
+      image.fill_rect(i * pitch, 0, grid_width, height, options.theme.grid);
+    }
+    for j in 0..=rows as u32 {
+      image.fill_rect(0, j * pitch, width, grid_width, options.theme.grid);
+    }
+  }
+
+  let mut draw_cells = |cells: &CellSet, color: Rgb| {
+    let visible = cells
+      .iter()
+      .filter(|cell| cell.x >= min.x && cell.x <= max.x && cell.y >= min.y && cell.y <= max.y);
+    for cell in visible {
+      image.fill_rect(
+        grid_width + (cell.x - min.x) as u32 * pitch,
+        grid_width + (cell.y - min.y) as u32 * pitch,
+        options.cell_size,
+        options.cell_size,
+        color,
+      );
+    }
+  };
+  let num_gens = previous_gens.len();
+  for gen_index in (0..num_gens).rev() {
+    let color = options.theme.trail_color(gen_index, num_gens);
+    draw_cells(&previous_gens[gen_index], color);
+  }
+  draw_cells(cells, options.theme.live_cell);
+
+  Ok(image)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn blinker() -> CellSet {
+    [(0, 1), (1, 1), (2, 1)]
+      .iter()
+      .map(|&(x, y)| Cell { x, y })
+      .collect()
+  }
+
+  #[test]
+  fn image_bounds_adds_margin_around_pattern() {
+    let bounds = image_bounds([&blinker()], 1);
+    assert_eq!(bounds, (Cell { x: -1, y: 0 }, Cell { x: 3, y: 2 }))
+  }
+
+  #[test]
+  fn image_bounds_of_empty_board_is_margin_around_origin() {
+    let bounds = image_bounds([&CellSet::new()], 2);
+    assert_eq!(bounds, (Cell { x: -2, y: -2 }, Cell { x: 2, y: 2 }))
+  }
+
+  #[test]
+  fn rasterize_draws_grid_and_cells() {
+    let options = ExportOptions::default();
+    let bounds = image_bounds([&blinker()], 0);
+    let image = rasterize(&blinker(), &[], bounds, &options).unwrap();
+    assert_eq!((image.width, image.height), (34, 12));
+    let pixel = |x: usize, y: usize| {
+      let index = 4 * (y * image.width as usize + x);
+      [
+        image.pixels[index],
+        image.pixels[index + 1],
+        image.pixels[index + 2],
+      ]
+    };
+    assert_eq!(pixel(0, 0), options.theme.grid);
+    assert_eq!(pixel(5, 5), options.theme.live_cell);
+    assert_eq!(pixel(11, 5), options.theme.grid);
+  }
+
+  #[test]
+  fn blend_rect_blends_partly_covered_pixels() {
+    let mut image = RgbaImage::new(4, 1, [255, 255, 255]);
+    image.blend_rect(0.5, 0.0, 2.0, 1.0, [0, 0, 0]);
+    assert_eq!(&image.pixels[0..3], &[128, 128, 128]);
+    assert_eq!(&image.pixels[4..7], &[0, 0, 0]);
+    assert_eq!(&image.pixels[8..11], &[128, 128, 128]);
+    assert_eq!(&image.pixels[12..15], &[255, 255, 255]);
+  }
+
+  #[test]
+  fn blend_rect_clips_to_the_image() {
+    let mut image = RgbaImage::new(2, 2, [255, 255, 255]);
+    image.blend_rect(-10.0, 1.0, 100.0, 100.0, [0, 0, 0]);
+    assert_eq!(&image.pixels[0..3], &[255, 255, 255]);
+    assert_eq!(&image.pixels[8..11], &[0, 0, 0]);
+    image.blend_rect(5.0, 5.0, 1.0, 1.0, [0, 0, 0]);
+  }
+
+  #[test]
+  fn rasterize_refuses_huge_images() {
+    let options = ExportOptions::default();
+    let bounds = (
+      Cell { x: 0, y: 0 },
+      Cell {
+        x: 100_000,
+        y: 100_000,
+      },
+    );
+    assert!(matches!(
+      rasterize(&CellSet::new(), &[], bounds, &options),
+      Err(ExportError::TooLarge(_, _))
+    ))
+  }
+}
@@ -0,0 +1,279 @@
+use super::ExportOptions;
+use crate::color_utils::{to_hex, Rgb};
+use crate::lexicon::Cell;
+use crate::life::CellSet;
+use crate::settings::Settings;
+use crate::viewport::{grid_width, pitch};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+
+/// Area of the board, in cell units (a cell `(x, y)` covers `x..x + 1` and
+/// `y..y + 1`).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Area {
+  pub x: f64,
+  pub y: f64,
+  pub width: f64,
+  pub height: f64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SvgScope {
+  /// Exactly what `Board` currently displays: the area, and the zoom it is
+  /// displayed at.
+  Viewport(Area, f64),
+  /// The pattern and its trail, plus the margin of the options, as `Board`
+  /// displays it at zoom 1.
+  BoundingBox,
+}
+
+/// Renders the board as an SVG document, laid out like `Board` lays it out
+/// with these settings: each cell is preceded by the space of a grid line,
+/// the grid is drawn first and the cells over it. Cells of a same colour are
+/// emitted as a single path, merged into rectangles when there is no space
+/// between them, so the output stays small for large patterns.
+pub fn render_svg(
+  cells: &CellSet,
+  previous_gens: &[CellSet],
+  scope: SvgScope,
+  options: &ExportOptions,
+  settings: &Settings,
+) -> String {
+  let (area, zoom) = match scope {
+    SvgScope::Viewport(area, zoom) => (area, zoom),
+    SvgScope::BoundingBox => {
+      let (min, max) =
+        super::image_bounds(std::iter::once(cells).chain(previous_gens), options.margin);
+      // With the grid line after the last cell too.
+      let gap = grid_width(1.0, settings) / pitch(1.0, settings);
+      let area = Area {
+        x: min.x as f64 - gap,
+        y: min.y as f64 - gap,
+        width: (max.x - min.x + 1) as f64 + gap,
+        height: (max.y - min.y + 1) as f64 + gap,
+      };
+      (area, 1.0)
+    }
+  };
+  // Width of the grid lines, in cells, the rest of the pitch being the cell.
+  let gap = grid_width(zoom, settings) / pitch(zoom, settings);
+
+  let mut svg = String::new();
+  writeln!(
+    svg,
+    r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" viewBox="{} {} {} {}" shape-rendering="crispEdges">"#,
+    number(area.width * options.cell_size as f64),
+    number(area.height * options.cell_size as f64),
+    number(area.x),
+    number(area.y),
+    number(area.width),
+    number(area.height),
+  )
+  .unwrap();
+  writeln!(
+    svg,
+    r#"<rect x="{}" y="{}" width="{}" height="{}" fill="{}"/>"#,
+    number(area.x),
+    number(area.y),
+    number(area.width),
+    number(area.height),
+    to_hex(options.theme.background),
+  )
+  .unwrap();
+
+  // The line of grid `i` ends where the cell `i` starts, like on the board.
+  if options.grid && zoom > settings.grid_min_zoom && gap > 0.0 {
+    let mut path = String::new();
+    for i in area.x.ceil() as i32..=(area.x + area.width + gap).floor() as i32 {
+      write!(
+        path,
+        "M{} {}h{}V{}h-{}z",
+        number(i as f64 - gap),
+        number(area.y),
+        number(gap),
+        number(area.y + area.height),
+        number(gap)
+      )
+      .unwrap();
+    }
+    for j in area.y.ceil() as i32..=(area.y + area.height + gap).floor() as i32 {
+      write!(
+        path,
+        "M{} {}v{}H{}v-{}z",
+        number(area.x),
+        number(j as f64 - gap),
+        number(gap),
+        number(area.x + area.width),
+        number(gap)
+      )
+      .unwrap();
+    }
+    writeln!(
+      svg,
+      r#"<path fill="{}" d="{}"/>"#,
+      to_hex(options.theme.grid),
+      path
+    )
+    .unwrap();
+  }
+
+  let num_gens = previous_gens.len();
+  for gen_index in (0..num_gens).rev() {
+    let color = options.theme.trail_color(gen_index, num_gens);
+    write_cells(&mut svg, &previous_gens[gen_index], area, gap, color);
+  }
+  write_cells(&mut svg, cells, area, gap, options.theme.live_cell);
+
+  svg.push_str("</svg>\n");
+  svg
+}
+
+/// Writes the cells as a path, each one `1 - gap` cells wide.
+fn write_cells(svg: &mut String, cells: &CellSet, area: Area, gap: f64, color: Rgb) {
+  let visible = cells.iter().copied().filter(|cell| {
+    (cell.x + 1) as f64 > area.x
+      && (cell.x as f64) < area.x + area.width
+      && (cell.y + 1) as f64 > area.y
+      && (cell.y as f64) < area.y + area.height
+  });
+  // Cells can only be merged when no grid line shows between them.
+  let rects = if gap > 0.0 {
+    let mut rects: Vec<_> = visible.map(|cell| (cell.x, cell.y, 1, 1)).collect();
+    rects.sort_unstable();
+    rects
+  } else {
+    merge_into_rects(visible)
+  };
+  if rects.is_empty() {
+    return;
+  }
+  let mut path = String::new();
+  for (x, y, width, height) in rects {
+    let (width, height) = (number(width as f64 - gap), number(height as f64 - gap));
+    write!(path, "M{} {}h{}v{}h-{}z", x, y, width, height, width).unwrap();
+  }
+  writeln!(svg, r#"<path fill="{}" d="{}"/>"#, to_hex(color), path).unwrap();
+}
+
+/// Covers `cells` with rectangles `(x, y, width, height)`: cells are first
+/// merged into horizontal runs, then identical runs on consecutive rows are
+/// merged together.
+fn merge_into_rects(cells: impl Iterator<Item = Cell>) -> Vec<(i32, i32, i32, i32)> {
+  let mut rows: BTreeMap<i32, Vec<i32>> = BTreeMap::new();
+  for cell in cells {
+    rows.entry(cell.y).or_default().push(cell.x);
+  }
+
+  let mut rects = vec![];
+  // Rectangles that may still grow downwards, by (x, width).
+  let mut open: HashMap<(i32, i32), (i32, i32)> = HashMap::new();
+  for (y, mut xs) in rows {
+    xs.sort_unstable();
+    let mut runs: Vec<(i32, i32)> = vec![];
+    for x in xs {
+      match runs.last_mut() {
+        Some((start, width)) if *start + *width == x => *width += 1,
+        _ => runs.push((x, 1)),
+      }
+    }
+
+    let mut still_open = HashMap::new();
+    for run in runs {
+      let (y_start, height) = match open.remove(&run) {
+        Some((y_start, height)) if y_start + height == y => (y_start, height + 1),
+        Some((y_start, height)) => {
+          rects.push((run.0, y_start, run.1, height));
+          (y, 1)
+        }
+        None => (y, 1),
+      };
+      still_open.insert(run, (y_start, height));
+    }
+    rects.extend(
+      open
+        .drain()
+        .map(|((x, width), (y_start, height))| (x, y_start, width, height)),
+    );
+    open = still_open;
+  }
+  rects.extend(
+    open
+      .into_iter()
+      .map(|((x, width), (y_start, height))| (x, y_start, width, height)),
+  );
+  rects.sort_unstable();
+  rects
+}
+
+/// Formats a coordinate with at most three decimals.
+fn number(value: f64) -> String {
+  let formatted = format!("{:.3}", value);
+  let formatted = formatted.trim_end_matches('0').trim_end_matches('.');
+  match formatted {
+    "-0" => "0".to_string(),
+    _ => formatted.to_string(),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::settings::default_settings;
+
+  fn cells(coords: &[(i32, i32)]) -> Vec<Cell> {
+    coords.iter().map(|&(x, y)| Cell { x, y }).collect()
+  }
+
+  #[test]
+  fn merge_into_rects_merges_runs() {
+    let rects = merge_into_rects(cells(&[(0, 0), (1, 0), (2, 0), (4, 0)]).into_iter());
+    assert_eq!(rects, vec![(0, 0, 3, 1), (4, 0, 1, 1)])
+  }
+
+  #[test]
+  fn merge_into_rects_merges_identical_runs_of_consecutive_rows() {
+    let block = cells(&[(0, 0), (1, 0), (0, 1), (1, 1), (0, 3), (1, 3)]);
+    assert_eq!(
+      merge_into_rects(block.into_iter()),
+      vec![(0, 0, 2, 2), (0, 3, 2, 1)]
+    )
+  }
+
+  #[test]
+  fn render_svg_draws_the_grid_between_cells_like_the_board() {
+    let settings = Settings {
+      cell_size: 3.0,
+      grid_width: 1.0,
+      ..default_settings()
+    };
+    let glider: CellSet = cells(&[(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)])
+      .into_iter()
+      .collect();
+    let options = ExportOptions {
+      margin: 0,
+      ..ExportOptions::default()
+    };
+    let svg = render_svg(&glider, &[], SvgScope::BoundingBox, &options, &settings);
+    // Grid lines a quarter of a cell wide, before each cell.
+    let grid = svg.find("M-0.25 -0.25h0.25V3h-0.25z").unwrap();
+    let cells = svg.find("M0 2h0.75v0.75h-0.75z").unwrap();
+    assert!(grid < cells);
+    // Adjacent cells aren't merged over the grid line between them.
+    assert!(svg.contains("M1 2h0.75v0.75h-0.75z"));
+
+    let no_grid = Settings {
+      grid_width: 0.0,
+      ..settings
+    };
+    let svg = render_svg(&glider, &[], SvgScope::BoundingBox, &options, &no_grid);
+    assert!(svg.contains("M0 2h3v1h-3z"));
+  }
+
+  #[test]
+  fn number_trims_useless_decimals() {
+    assert_eq!(number(2.0), "2");
+    assert_eq!(number(-0.25), "-0.25");
+    assert_eq!(number(1.0 / 3.0), "0.333");
+    assert_eq!(number(-0.0001), "0");
+  }
+}
@@ -0,0 +1,47 @@
+//! Web Worker rendering animated GIFs, so that the page stays responsive
+//! while hundreds of generations are run, rasterized and encoded.
+
+use super::{render_animation, AnimationFormat, ExportOptions};
+use crate::life::CellSet;
+use gloo_worker::{HandlerId, Worker, WorkerScope};
+use serde::{Deserialize, Serialize};
+
+/// Path of the script of the worker, built by Trunk from
+/// `src/bin/export-worker.rs`.
+pub const EXPORT_WORKER_PATH: &str = "/export-worker.js";
+
+pub struct ExportWorker;
+
+#[derive(Serialize, Deserialize)]
+pub struct Input {
+  pub cells: CellSet,
+  /// Number of generations to animate, from `cells`.
+  pub generations: u32,
+  pub trail_length: usize,
+  pub options: ExportOptions,
+}
+
+impl Worker for ExportWorker {
+  type Message = ();
+  type Input = Input;
+  /// The GIF file, or why it couldn't be rendered.
+  type Output = Result<Vec<u8>, String>;
+
+  fn create(_: &WorkerScope<Self>) -> Self {
+    Self
+  }
+
+  fn update(&mut self, _: &WorkerScope<Self>, _: Self::Message) {}
+
+  fn received(&mut self, scope: &WorkerScope<Self>, input: Self::Input, id: HandlerId) {
+    let gif = render_animation(
+      &input.cells,
+      0..input.generations,
+      input.trail_length,
+      &input.options,
+      AnimationFormat::Gif,
+    )
+    .map_err(|error| error.to_string());
+    scope.respond(id, gif);
+  }
+}
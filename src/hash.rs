@@ -0,0 +1,11 @@
+//! Hashes stable across platforms and versions, unlike `DefaultHasher`, for
+//! values that are stored or shared.
+
+/// FNV-1a hash of the bytes.
+pub fn fnv1a(bytes: impl IntoIterator<Item = u8>) -> u64 {
+  bytes
+    .into_iter()
+    .fold(0xcbf29ce484222325_u64, |hash, byte| {
+      (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
@@ -0,0 +1,219 @@
+use serde::{Deserialize, Serialize};
+
+/// Something the user can do from the keyboard or the command palette.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Action {
+  PlayPause,
+  Tick,
+  ZoomIn,
+  ZoomOut,
+  PanLeft,
+  PanRight,
+  PanUp,
+  PanDown,
+  SlowDown,
+  SpeedUp,
+  Recenter,
+  FitAll,
+  CommandPalette,
+  OpenSettings,
+  LabelObjects,
+}
+
+impl Action {
+  pub const ALL: [Action; 15] = [
+    Action::PlayPause,
+    Action::Tick,
+    Action::ZoomIn,
+    Action::ZoomOut,
+    Action::PanLeft,
+    Action::PanRight,
+    Action::PanUp,
+    Action::PanDown,
+    Action::SlowDown,
+    Action::SpeedUp,
+    Action::Recenter,
+    Action::FitAll,
+    Action::CommandPalette,
+    Action::OpenSettings,
+    Action::LabelObjects,
+  ];
+
+  pub fn label(self) -> &'static str {
+    match self {
+      Action::PlayPause => "Play / pause",
+      Action::Tick => "Next generation",
+      Action::ZoomIn => "Zoom in",
+      Action::ZoomOut => "Zoom out",
+      Action::PanLeft => "Pan left",
+      Action::PanRight => "Pan right",
+      Action::PanUp => "Pan up",
+      Action::PanDown => "Pan down",
+      Action::SlowDown => "Slow down",
+      Action::SpeedUp => "Speed up",
+      Action::Recenter => "Recentre the view",
+      Action::FitAll => "Fit the whole population",
+      Action::CommandPalette => "Open the command palette",
+      Action::OpenSettings => "Open the settings",
+      Action::LabelObjects => "Show / hide the object labels",
+    }
+  }
+}
+
+/// Keys bound to actions. A key is written the way `key_name` formats it,
+/// e.g. "Space", "N" or "Ctrl+K". Several keys can trigger the same action,
+/// but a key triggers only one.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct KeyBindings {
+  bindings: Vec<(String, Action)>,
+}
+
+impl Default for KeyBindings {
+  fn default() -> Self {
+    let bindings = [
+      ("Space", Action::PlayPause),
+      ("N", Action::Tick),
+      ("Enter", Action::Tick),
+      ("+", Action::ZoomIn),
+      ("=", Action::ZoomIn),
+      ("-", Action::ZoomOut),
+      ("ArrowLeft", Action::PanLeft),
+      ("ArrowRight", Action::PanRight),
+      ("ArrowUp", Action::PanUp),
+      ("ArrowDown", Action::PanDown),
+      ("[", Action::SlowDown),
+      ("]", Action::SpeedUp),
+      ("Home", Action::Recenter),
+      ("Ctrl+K", Action::CommandPalette),
+      ("Meta+K", Action::CommandPalette),
+      (",", Action::OpenSettings),
+      ("L", Action::LabelObjects),
+    ];
+    Self {
+      bindings: bindings
+        .iter()
+        .map(|&(key, action)| (key.to_string(), action))
+        .collect(),
+    }
+  }
+}
+
+impl KeyBindings {
+  pub fn action_for(&self, key: &str) -> Option<Action> {
+    self
+      .bindings
+      .iter()
+      .find(|(bound_key, _)| bound_key == key)
+      .map(|&(_, action)| action)
+  }
+
+  pub fn keys_for(&self, action: Action) -> Vec<&str> {
+    self
+      .bindings
+      .iter()
+      .filter(|&&(_, bound_action)| bound_action == action)
+      .map(|(key, _)| key.as_str())
+      .collect()
+  }
+
+  /// Binds `key` to `action`, unbinding it from any other action.
+  pub fn bind(&mut self, key: &str, action: Action) {
+    self.unbind(key);
+    self.bindings.push((key.to_string(), action));
+  }
+
+  pub fn unbind(&mut self, key: &str) {
+    self.bindings.retain(|(bound_key, _)| bound_key != key);
+  }
+
+  /// Binds the default keys of the actions without any key, e.g. the ones
+  /// added since these bindings were saved, unless the keys are already
+  /// bound to other actions.
+  pub fn bind_defaults_of_unbound(&mut self) {
+    let unbound: Vec<Action> = Action::ALL
+      .iter()
+      .copied()
+      .filter(|&action| self.keys_for(action).is_empty())
+      .collect();
+    for (key, action) in KeyBindings::default().bindings {
+      if unbound.contains(&action) && self.action_for(&key).is_none() {
+        self.bindings.push((key, action));
+      }
+    }
+  }
+}
+
+/// Name of a key pressed with modifiers, from the `key` of a keyboard event.
+/// Shift isn't part of the name, as it already changes the key itself (e.g.
+/// "+" instead of "=").
+pub fn key_name(key: &str, ctrl: bool, alt: bool, meta: bool) -> String {
+  let mut name = String::new();
+  if ctrl {
+    name.push_str("Ctrl+");
+  }
+  if alt {
+    name.push_str("Alt+");
+  }
+  if meta {
+    name.push_str("Meta+");
+  }
+  match key {
+    " " => name.push_str("Space"),
+    key if key.chars().count() == 1 => name.push_str(&key.to_uppercase()),
+    key => name.push_str(key),
+  }
+  name
+}
+
+/// Whether `key` is a modifier, that can't be bound alone.
+pub fn is_modifier(key: &str) -> bool {
+  matches!(key, "Control" | "Alt" | "Shift" | "Meta" | "AltGraph")
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn key_name_adds_modifiers_and_normalizes_keys() {
+    assert_eq!(key_name(" ", false, false, false), "Space");
+    assert_eq!(key_name("n", false, false, false), "N");
+    assert_eq!(key_name("k", true, false, false), "Ctrl+K");
+    assert_eq!(key_name("ArrowLeft", false, true, false), "Alt+ArrowLeft");
+    assert_eq!(key_name("+", false, false, false), "+");
+  }
+
+  #[test]
+  fn default_bindings_match_documented_keys() {
+    let bindings = KeyBindings::default();
+    assert_eq!(bindings.action_for("Space"), Some(Action::PlayPause));
+    assert_eq!(bindings.keys_for(Action::Tick), vec!["N", "Enter"]);
+    assert_eq!(bindings.action_for("Ctrl+K"), Some(Action::CommandPalette));
+    assert_eq!(bindings.action_for("Q"), None);
+  }
+
+  #[test]
+  fn binding_a_key_removes_it_from_other_actions() {
+    let mut bindings = KeyBindings::default();
+    bindings.bind("N", Action::FitAll);
+    assert_eq!(bindings.action_for("N"), Some(Action::FitAll));
+    assert_eq!(bindings.keys_for(Action::Tick), vec!["Enter"]);
+    bindings.unbind("Enter");
+    assert!(bindings.keys_for(Action::Tick).is_empty());
+  }
+
+  #[test]
+  fn unbound_actions_get_their_default_keys() {
+    // Bindings saved before opening the settings could be bound.
+    let mut bindings = KeyBindings::default();
+    bindings.unbind(",");
+    bindings.bind("Ctrl+K", Action::FitAll);
+    bindings.unbind("Meta+K");
+    bindings.bind_defaults_of_unbound();
+    assert_eq!(bindings.keys_for(Action::OpenSettings), vec![","]);
+    // Only the default key not bound to another action.
+    assert_eq!(bindings.keys_for(Action::CommandPalette), vec!["Meta+K"]);
+    assert_eq!(bindings.keys_for(Action::FitAll), vec!["Ctrl+K"]);
+    assert_eq!(bindings.keys_for(Action::Tick), vec!["N", "Enter"]);
+  }
+}
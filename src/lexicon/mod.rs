@@ -1,16 +1,31 @@
 use serde::{Deserialize, Serialize};
-use std::fmt::Debug;
+use std::fmt::{self, Debug};
+use std::sync::OnceLock;
+
+mod parser;
+mod rle;
+
+pub use parser::parse_lexicon;
+pub use rle::{decode_rle, encode_rle};
 
 #[derive(Debug)]
 pub struct ParseError(String);
 
+impl fmt::Display for ParseError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str(&self.0)
+  }
+}
+
+impl std::error::Error for ParseError {}
+
 #[derive(Hash, Eq, PartialEq, Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct Cell {
   pub x: i32,
   pub y: i32,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Term {
   pub name: String,
   pub description: String,
@@ -20,19 +35,52 @@ pub struct Term {
   pub height: usize,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct Lexicon {
   pub terms: Vec<Term>,
 }
 
 impl Lexicon {
+  /// The lexicon embedded in the application, or an empty one if it can't be
+  /// read. It is only deserialized the first time.
+  pub fn get() -> &'static Lexicon {
+    static LEXICON: OnceLock<Lexicon> = OnceLock::new();
+    LEXICON.get_or_init(|| get_lexicon().unwrap_or_default())
+  }
+
   pub fn get_term(&self, name: String) -> Option<&Term> {
     self.terms.iter().find(|term| term.name == name)
   }
 }
 
+/// Reads the lexicon embedded in the application, generated from the text
+/// version of the Life Lexicon by the `build-lexicon` binary.
 pub fn get_lexicon() -> Option<Lexicon> {
   let serialized = include_bytes!("lexicon.bin");
-  let lexicon: Lexicon = bincode::deserialize(serialized).unwrap();
-  Some(lexicon)
+  bincode::deserialize(serialized).ok()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn embedded_lexicon_can_be_read() {
+    let lexicon = get_lexicon().expect("lexicon.bin to be valid");
+    assert!(lexicon.get_term("blinker".to_string()).is_some());
+  }
+
+  #[test]
+  fn embedded_lexicon_is_built_from_the_vendored_source() {
+    let source: String = include_bytes!("lexicon.txt")
+      .iter()
+      .map(|&b| b as char)
+      .collect();
+    let (lexicon, _) = parse_lexicon(&source);
+    let serialized = bincode::serialize(&lexicon).unwrap();
+    assert!(
+      serialized == include_bytes!("lexicon.bin"),
+      "lexicon.bin is out of date, regenerate it with build-lexicon"
+    );
+  }
 }
@@ -0,0 +1,225 @@
+//! Parser for the text version of the [Life Lexicon](https://conwaylife.com/ref/lexicon/),
+//! where each entry looks like:
+//!
+//! ```text
+//! :blinker: (p2) The smallest and most common {oscillator}.
+//!     OOO
+//! ```
+//!
+//! Entries start with `:name:` at the beginning of a line, optionally followed
+//! by tags between parentheses. Following lines are indented, and contain
+//! either the description or a diagram made of `.` (dead) and `O` or `*`
+//! (alive) cells.
+
+use super::{Cell, Lexicon, ParseError, Term};
+
+struct Entry {
+  line: usize,
+  header: String,
+  body: Vec<String>,
+}
+
+/// Parses the whole lexicon. Entries that can't be parsed are skipped and
+/// reported as errors, so that a single broken entry doesn't prevent building
+/// the lexicon.
+pub fn parse_lexicon(source: &str) -> (Lexicon, Vec<ParseError>) {
+  let mut entries: Vec<Entry> = vec![];
+  let mut in_entry = false;
+  for (i, line) in source.lines().enumerate() {
+    let line = line.trim_end();
+    if line.starts_with(':') {
+      entries.push(Entry {
+        line: i + 1,
+        header: line.to_string(),
+        body: vec![],
+      });
+      in_entry = true;
+    } else if line.starts_with(|c: char| c.is_whitespace()) {
+      if in_entry {
+        entries
+          .last_mut()
+          .unwrap()
+          .body
+          .push(line.trim().to_string());
+      }
+    } else if !line.is_empty() {
+      // Introduction, section titles and bibliography aren't entries.
+      in_entry = false;
+    }
+  }
+
+  let mut terms = vec![];
+  let mut errors = vec![];
+  for entry in entries {
+    match parse_entry(&entry) {
+      Ok(term) => terms.push(term),
+      Err(error) => errors.push(ParseError(format!("line {}: {}", entry.line, error))),
+    }
+  }
+  (Lexicon { terms }, errors)
+}
+
+fn parse_entry(entry: &Entry) -> Result<Term, String> {
+  let header = &entry.header[1..];
+  let name_end = header
+    .find(':')
+    .ok_or_else(|| "missing closing ':' after the name".to_string())?;
+  let name = header[..name_end].trim().to_string();
+  if name.is_empty() {
+    return Err("empty name".to_string());
+  }
+  let rest = header[name_end + 1..].trim_start();
+  let (tags, first_line) = parse_tags(rest)?;
+
+  let mut description_lines = vec![first_line.to_string()];
+  let mut diagram: Vec<&str> = vec![];
+  let mut diagram_done = false;
+  for line in &entry.body {
+    if is_diagram_line(line) {
+      if !diagram_done {
+        diagram.push(line);
+      }
+    } else {
+      // Only the first diagram of an entry is kept.
+      diagram_done = diagram_done || !diagram.is_empty();
+      description_lines.push(line.clone());
+    }
+  }
+  let description = description_lines
+    .iter()
+    .map(|line| line.trim())
+    .filter(|line| !line.is_empty())
+    .collect::<Vec<_>>()
+    .join(" ");
+
+  let (cells, width, height) = parse_diagram(&diagram);
+  Ok(Term {
+    name,
+    description,
+    tags,
+    cells,
+    width,
+    height,
+  })
+}
+
+/// Parses the tags between parentheses at the beginning of `text`, which may
+/// themselves contain parentheses, e.g. `((2,1)c/6, p6)`.
+fn parse_tags(text: &str) -> Result<(Vec<String>, &str), String> {
+  if !text.starts_with('(') {
+    return Ok((vec![], text));
+  }
+  let mut tags = vec![];
+  let mut depth = 0;
+  let mut tag_start = 1;
+  for (i, c) in text.char_indices() {
+    match c {
+      '(' => depth += 1,
+      ')' => {
+        depth -= 1;
+        if depth == 0 {
+          tags.push(text[tag_start..i].trim().to_string());
+          let tags = tags.into_iter().filter(|tag| !tag.is_empty()).collect();
+          return Ok((tags, text[i + 1..].trim_start()));
+        }
+      }
+      ',' if depth == 1 => {
+        tags.push(text[tag_start..i].trim().to_string());
+        tag_start = i + 1;
+      }
+      _ => {}
+    }
+  }
+  Err("unbalanced parentheses in tags".to_string())
+}
+
+fn is_diagram_line(line: &str) -> bool {
+  !line.is_empty() && line.chars().all(|c| matches!(c, '.' | 'O' | '*'))
+}
+
+fn parse_diagram(lines: &[&str]) -> (Vec<Cell>, usize, usize) {
+  let cells = lines
+    .iter()
+    .enumerate()
+    .flat_map(|(y, line)| {
+      line
+        .chars()
+        .enumerate()
+        .filter(|(_, c)| *c != '.')
+        .map(move |(x, _)| Cell {
+          x: x as i32,
+          y: y as i32,
+        })
+    })
+    .collect();
+  let width = lines.iter().map(|line| line.len()).max().unwrap_or(0);
+  (cells, width, lines.len())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const SOURCE: &str = "LIFE LEXICON
+
+Introduction, not an entry.
+
+:blinker: (p2) The smallest and most common {oscillator}.
+\tSee also {blonker}.
+\tOOO
+
+:block: (p1) The most common {still life}.
+\t**
+\t**
+\tFound by everyone.
+
+:broken (p2) Missing colon.
+
+:glider: ((1,1)c/4, p4) The smallest {spaceship}.
+\t.O.
+\t..O
+\tOOO
+\t.....
+\tO....
+
+BIBLIOGRAPHY
+";
+
+  #[test]
+  fn parse_lexicon_parses_names_tags_and_descriptions() {
+    let (lexicon, _) = parse_lexicon(SOURCE);
+    let names: Vec<&str> = lexicon.terms.iter().map(|t| t.name.as_str()).collect();
+    assert_eq!(names, vec!["blinker", "block", "glider"]);
+    let blinker = &lexicon.terms[0];
+    assert_eq!(blinker.tags, vec!["p2".to_string()]);
+    assert_eq!(
+      blinker.description,
+      "The smallest and most common {oscillator}. See also {blonker}."
+    );
+    assert_eq!(lexicon.terms[2].tags, vec!["(1,1)c/4", "p4"]);
+  }
+
+  #[test]
+  fn parse_lexicon_parses_first_diagram() {
+    let (lexicon, _) = parse_lexicon(SOURCE);
+    let block = &lexicon.terms[1];
+    assert_eq!((block.width, block.height, block.cells.len()), (2, 2, 4));
+    assert_eq!(
+      block.description,
+      "The most common {still life}. Found by everyone."
+    );
+    let glider = &lexicon.terms[2];
+    assert_eq!((glider.width, glider.height), (5, 5));
+    assert_eq!(glider.cells[0], Cell { x: 1, y: 0 });
+  }
+
+  #[test]
+  fn parse_lexicon_reports_broken_entries() {
+    let (_, errors) = parse_lexicon(SOURCE);
+    assert_eq!(errors.len(), 1);
+    assert_eq!(
+      errors[0].to_string(),
+      "line 14: missing closing ':' after the name"
+    );
+  }
+}
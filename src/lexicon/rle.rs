@@ -0,0 +1,267 @@
+//! Reading and writing patterns in the [RLE format](https://conwaylife.com/wiki/Run_Length_Encoded),
+//! the most common format to share patterns.
+//!
+//! The name is stored as a `#N` line and the description as `#C` lines. Tags,
+//! which have no equivalent in RLE, are stored in a `#C Tags:` line.
+
+use super::{Cell, ParseError, Term};
+use std::collections::HashSet;
+
+const MAX_LINE_LENGTH: usize = 70;
+const TAGS_PREFIX: &str = "Tags: ";
+
+pub fn encode_rle(term: &Term) -> String {
+  let mut rle = String::new();
+  if !term.name.is_empty() {
+    rle.push_str(&format!("#N {}\n", term.name));
+  }
+  for line in term.description.lines() {
+    rle.push_str(&format!("#C {}\n", line));
+  }
+  if !term.tags.is_empty() {
+    rle.push_str(&format!("#C {}{}\n", TAGS_PREFIX, term.tags.join(", ")));
+  }
+  rle.push_str(&format!(
+    "x = {}, y = {}, rule = B3/S23\n",
+    term.width, term.height
+  ));
+
+  let alive: HashSet<Cell> = term.cells.iter().copied().collect();
+  let mut tokens: Vec<String> = vec![];
+  let mut pending_rows = 0;
+  for y in 0..term.height as i32 {
+    let mut runs: Vec<(usize, char)> = vec![];
+    for x in 0..term.width as i32 {
+      let state = if alive.contains(&Cell { x, y }) {
+        'o'
+      } else {
+        'b'
+      };
+      match runs.last_mut() {
+        Some((count, last)) if *last == state => *count += 1,
+        _ => runs.push((1, state)),
+      }
+    }
+    if let Some((_, 'b')) = runs.last() {
+      runs.pop();
+    }
+    if runs.is_empty() {
+      pending_rows += 1;
+      continue;
+    }
+    if !tokens.is_empty() {
+      tokens.push(run_token(pending_rows + 1, '$'));
+    }
+    pending_rows = 0;
+    tokens.extend(
+      runs
+        .into_iter()
+        .map(|(count, state)| run_token(count, state)),
+    );
+  }
+  tokens.push("!".to_string());
+
+  let mut line_length = 0;
+  for token in tokens {
+    if line_length + token.len() > MAX_LINE_LENGTH {
+      rle.push('\n');
+      line_length = 0;
+    }
+    line_length += token.len();
+    rle.push_str(&token);
+  }
+  rle.push('\n');
+  rle
+}
+
+fn run_token(count: usize, state: char) -> String {
+  if count == 1 {
+    state.to_string()
+  } else {
+    format!("{}{}", count, state)
+  }
+}
+
+pub fn decode_rle(source: &str) -> Result<Term, ParseError> {
+  let mut name = String::new();
+  let mut description_lines: Vec<&str> = vec![];
+  let mut tags = vec![];
+  let mut header = None;
+  let mut body = String::new();
+  for line in source.lines().map(str::trim) {
+    if header.is_none() {
+      if let Some(comment) = line.strip_prefix("#N") {
+        name = comment.trim().to_string();
+      } else if let Some(comment) = line.strip_prefix("#C").or(line.strip_prefix("#c")) {
+        let comment = comment.trim();
+        match comment.strip_prefix(TAGS_PREFIX) {
+          Some(tag_list) => tags = tag_list.split(", ").map(String::from).collect(),
+          None => description_lines.push(comment),
+        }
+      } else if line.starts_with('x') {
+        header = Some(parse_header(line)?);
+      } else if !line.is_empty() && !line.starts_with('#') {
+        return Err(ParseError(format!(
+          "unexpected line before header: {}",
+          line
+        )));
+      }
+    } else {
+      body.push_str(line);
+    }
+  }
+  let (header_width, header_height) =
+    header.ok_or_else(|| ParseError("missing “x = …, y = …” header".to_string()))?;
+
+  // Live cells must be inside the header, so a huge count like `999999999o`
+  // can't make the pattern arbitrarily large.
+  let (width, height) = (header_width as i64, header_height as i64);
+  let mut cells = vec![];
+  let (mut x, mut y): (i64, i64) = (0, 0);
+  let mut count = String::new();
+  for c in body.chars() {
+    if c.is_ascii_digit() {
+      count.push(c);
+      continue;
+    }
+    let n: i64 = if count.is_empty() {
+      1
+    } else {
+      count
+        .parse()
+        .map_err(|_| ParseError(format!("invalid run count {}", count)))?
+    };
+    match c {
+      '!' => break,
+      '$' => {
+        x = 0;
+        y = y.saturating_add(n);
+      }
+      'b' | '.' => x = x.saturating_add(n),
+      c if c.is_ascii_alphabetic() => {
+        if x.saturating_add(n) > width || y >= height {
+          return Err(ParseError(format!(
+            "run {}{} outside the {} × {} header",
+            count, c, header_width, header_height
+          )));
+        }
+        cells.extend((x..x + n).map(|x| Cell {
+          x: x as i32,
+          y: y as i32,
+        }));
+        x += n;
+      }
+      c if c.is_whitespace() => {}
+      c => return Err(ParseError(format!("unexpected character “{}”", c))),
+    }
+    count.clear();
+  }
+
+  Ok(Term {
+    name,
+    description: description_lines.join("\n"),
+    tags,
+    cells,
+    width: header_width,
+    height: header_height,
+  })
+}
+
+fn parse_header(line: &str) -> Result<(usize, usize), ParseError> {
+  let mut width = None;
+  let mut height = None;
+  for part in line.split(',') {
+    let (key, value) = match part.split_once('=') {
+      Some((key, value)) => (key.trim(), value.trim()),
+      None => continue,
+    };
+    let parse = |value: &str| {
+      value
+        .parse::<usize>()
+        .map_err(|_| ParseError(format!("invalid size in header: {}", line)))
+    };
+    match key {
+      "x" => width = Some(parse(value)?),
+      "y" => height = Some(parse(value)?),
+      "rule" if !matches!(value.to_uppercase().as_str(), "B3/S23" | "23/3") => {
+        return Err(ParseError(format!("unsupported rule {}", value)))
+      }
+      _ => {}
+    }
+  }
+  match (width, height) {
+    (Some(width), Some(height)) => Ok((width, height)),
+    _ => Err(ParseError(format!("invalid header: {}", line))),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn glider() -> Term {
+    Term {
+      name: "Glider".to_string(),
+      description: "The smallest spaceship.".to_string(),
+      tags: vec!["c/4 diagonally".to_string(), "p4".to_string()],
+      cells: vec![
+        Cell { x: 1, y: 0 },
+        Cell { x: 2, y: 1 },
+        Cell { x: 0, y: 2 },
+        Cell { x: 1, y: 2 },
+        Cell { x: 2, y: 2 },
+      ],
+      width: 3,
+      height: 3,
+    }
+  }
+
+  #[test]
+  fn encode_rle_writes_name_comments_and_runs() {
+    assert_eq!(
+      encode_rle(&glider()),
+      "#N Glider\n#C The smallest spaceship.\n#C Tags: c/4 diagonally, p4\nx = 3, y = 3, rule = B3/S23\nbo$2bo$3o!\n"
+    )
+  }
+
+  #[test]
+  fn encode_rle_merges_empty_rows() {
+    let term = Term {
+      name: String::new(),
+      description: String::new(),
+      tags: vec![],
+      cells: vec![Cell { x: 0, y: 0 }, Cell { x: 0, y: 3 }],
+      width: 1,
+      height: 4,
+    };
+    assert_eq!(encode_rle(&term), "x = 1, y = 4, rule = B3/S23\no3$o!\n")
+  }
+
+  #[test]
+  fn decode_rle_reads_encoded_term() {
+    let term = decode_rle(&encode_rle(&glider())).unwrap();
+    assert_eq!(term, glider())
+  }
+
+  #[test]
+  fn decode_rle_reads_multiline_bodies() {
+    let term = decode_rle("#C A blinker\nx = 3, y = 1\n2o\no!").unwrap();
+    assert_eq!(term.description, "A blinker");
+    assert_eq!(term.cells.len(), 3);
+    assert_eq!((term.width, term.height), (3, 1));
+  }
+
+  #[test]
+  fn decode_rle_rejects_other_rules() {
+    assert!(decode_rle("x = 1, y = 1, rule = B36/S23\no!").is_err());
+    assert!(decode_rle("bo$2bo$3o!").is_err());
+  }
+
+  #[test]
+  fn decode_rle_rejects_runs_outside_the_header() {
+    assert!(decode_rle("x = 3, y = 1\n999999999o!").is_err());
+    assert!(decode_rle("x = 3, y = 1\n2b2o!").is_err());
+    assert!(decode_rle("x = 3, y = 2\no2$o!").is_err());
+    assert!(decode_rle("x = 3, y = 2\n3o$3o$!").is_ok());
+  }
+}
@@ -0,0 +1,74 @@
+use yew::prelude::*;
+
+pub mod batch;
+pub mod census;
+pub mod color_utils;
+mod components;
+mod description;
+mod download;
+pub mod engine;
+pub mod export;
+mod hash;
+mod keybindings;
+pub mod lexicon;
+mod library;
+pub mod life;
+pub mod predecessor;
+pub mod render;
+pub mod sat;
+mod search;
+pub mod settings;
+pub mod soup;
+mod speed;
+mod stop;
+mod storage;
+pub mod theme;
+mod viewport;
+
+use components::game::Game;
+use gloo::events::EventListener;
+use settings::{load_settings, save_settings, Settings};
+use theme::Theme;
+use web_sys::MediaQueryList;
+
+/// Media query matching when the user prefers a dark color scheme.
+fn dark_scheme_query() -> Option<MediaQueryList> {
+  web_sys::window()?
+    .match_media("(prefers-color-scheme: dark)")
+    .ok()?
+}
+
+#[function_component(App)]
+pub fn app() -> Html {
+  let settings = use_state(load_settings);
+  let prefers_dark = use_state(|| dark_scheme_query().is_some_and(|query| query.matches()));
+  {
+    let prefers_dark = prefers_dark.clone();
+    use_effect_with_deps(
+      move |_| {
+        let listener = dark_scheme_query().map(|query| {
+          EventListener::new(&query.clone(), "change", move |_| {
+            prefers_dark.set(query.matches())
+          })
+        });
+        move || drop(listener)
+      },
+      (),
+    );
+  }
+  let on_change_settings = {
+    let settings = settings.clone();
+    Callback::from(move |new_settings: Settings| {
+      save_settings(&new_settings);
+      settings.set(new_settings);
+    })
+  };
+  let theme = settings.theme.theme(*prefers_dark);
+  html! {
+    <ContextProvider<Settings> context={(*settings).clone()}>
+      <ContextProvider<Theme> context={theme}>
+        <Game on_change_settings={on_change_settings}/>
+      </ContextProvider<Theme>>
+    </ContextProvider<Settings>>
+  }
+}
@@ -0,0 +1,242 @@
+//! Patterns saved by the user, stored in the browser next to the built-in
+//! lexicon.
+
+use crate::lexicon::{decode_rle, encode_rle, Cell, Term};
+use crate::life::{bounding_box, CellSet};
+use crate::storage;
+use serde::{Deserialize, Serialize};
+use std::io::{Cursor, Read, Write};
+
+const STORAGE_KEY: &str = "lifeee.library";
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct UserPattern {
+  /// Folder the pattern is organised in, empty for the top level.
+  pub folder: String,
+  pub term: Term,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct UserLibrary {
+  pub patterns: Vec<UserPattern>,
+}
+
+impl UserLibrary {
+  pub fn load() -> Self {
+    storage::load(STORAGE_KEY).unwrap_or_default()
+  }
+
+  pub fn save(&self) {
+    storage::save(STORAGE_KEY, self);
+  }
+
+  /// Names of the folders in use, sorted.
+  pub fn folders(&self) -> Vec<String> {
+    let mut folders: Vec<String> = self
+      .patterns
+      .iter()
+      .map(|pattern| pattern.folder.clone())
+      .filter(|folder| !folder.is_empty())
+      .collect();
+    folders.sort();
+    folders.dedup();
+    folders
+  }
+
+  /// Adds a pattern, replacing the one with the same name in the same folder.
+  pub fn add(&mut self, pattern: UserPattern) {
+    match self
+      .patterns
+      .iter_mut()
+      .find(|p| p.folder == pattern.folder && p.term.name == pattern.term.name)
+    {
+      Some(existing) => *existing = pattern,
+      None => self.patterns.push(pattern),
+    }
+  }
+
+  pub fn merge(&mut self, other: UserLibrary) {
+    for pattern in other.patterns {
+      self.add(pattern);
+    }
+  }
+
+  pub fn to_json(&self) -> String {
+    serde_json::to_string_pretty(self).expect("library to be serializable")
+  }
+
+  pub fn from_json(json: &str) -> Result<Self, String> {
+    serde_json::from_str(json).map_err(|error| format!("invalid library file: {}", error))
+  }
+
+  /// Zip archive with one RLE file per pattern, in a directory per folder.
+  pub fn to_zip(&self) -> Vec<u8> {
+    let mut zip = zip::ZipWriter::new(Cursor::new(vec![]));
+    let options =
+      zip::write::FileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    let mut used_paths: Vec<String> = vec![];
+    for (i, pattern) in self.patterns.iter().enumerate() {
+      let stem = file_stem(pattern, i);
+      let mut path = format!("{}.rle", stem);
+      let mut copy = 2;
+      while used_paths.contains(&path) {
+        path = format!("{} ({}).rle", stem, copy);
+        copy += 1;
+      }
+      zip
+        .start_file(path.clone(), options)
+        .expect("writing to memory not to fail");
+      zip
+        .write_all(encode_rle(&pattern.term).as_bytes())
+        .expect("writing to memory not to fail");
+      used_paths.push(path);
+    }
+    zip
+      .finish()
+      .expect("writing to memory not to fail")
+      .into_inner()
+  }
+
+  pub fn from_zip(bytes: &[u8]) -> Result<Self, String> {
+    let mut archive = zip::ZipArchive::new(Cursor::new(bytes))
+      .map_err(|error| format!("invalid zip file: {}", error))?;
+    let mut library = UserLibrary::default();
+    for i in 0..archive.len() {
+      let mut file = archive.by_index(i).map_err(|error| error.to_string())?;
+      if file.is_dir() || !file.name().to_lowercase().ends_with(".rle") {
+        continue;
+      }
+      let path = file.name().to_string();
+      let mut source = String::new();
+      file
+        .read_to_string(&mut source)
+        .map_err(|error| format!("{}: {}", path, error))?;
+      library.patterns.push(pattern_from_rle(&path, &source)?);
+    }
+    Ok(library)
+  }
+}
+
+/// Reads a pattern from an RLE file, the name of the file being used when the
+/// pattern has no name, and its directory as folder.
+pub fn pattern_from_rle(path: &str, source: &str) -> Result<UserPattern, String> {
+  let mut term = decode_rle(source).map_err(|error| format!("{}: {}", path, error))?;
+  let (folder, file_name) = match path.rsplit_once('/') {
+    Some((folder, file_name)) => (folder.to_string(), file_name),
+    None => (String::new(), path),
+  };
+  if term.name.is_empty() {
+    let extension_start = file_name.rfind('.').unwrap_or(file_name.len());
+    term.name = file_name[..extension_start].to_string();
+  }
+  Ok(UserPattern { folder, term })
+}
+
+/// Path of the file of a pattern in an archive, without extension.
+fn file_stem(pattern: &UserPattern, index: usize) -> String {
+  let sanitize = |name: &str| -> String {
+    name
+      .chars()
+      .map(|c| match c {
+        '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+        c => c,
+      })
+      .collect()
+  };
+  let name = match sanitize(&pattern.term.name) {
+    name if name.is_empty() => format!("pattern-{}", index + 1),
+    name => name,
+  };
+  if pattern.folder.is_empty() {
+    name
+  } else {
+    format!("{}/{}", sanitize(&pattern.folder), name)
+  }
+}
+
+/// Makes a term out of the cells in `region` (or all cells), moved so that the
+/// pattern starts at the origin.
+pub fn term_from_cells(
+  name: String,
+  description: String,
+  tags: Vec<String>,
+  cells: &CellSet,
+  region: Option<(Cell, Cell)>,
+) -> Term {
+  let cells: CellSet = match region {
+    Some((min, max)) => cells
+      .iter()
+      .copied()
+      .filter(|cell| cell.x >= min.x && cell.x <= max.x && cell.y >= min.y && cell.y <= max.y)
+      .collect(),
+    None => cells.clone(),
+  };
+  let (min, max) = bounding_box(&cells).unwrap_or((Cell { x: 0, y: 0 }, Cell { x: -1, y: -1 }));
+  let mut cells: Vec<Cell> = cells
+    .iter()
+    .map(|cell| Cell {
+      x: cell.x - min.x,
+      y: cell.y - min.y,
+    })
+    .collect();
+  cells.sort_by_key(|cell| (cell.y, cell.x));
+  Term {
+    name,
+    description,
+    tags,
+    cells,
+    width: (max.x - min.x + 1) as usize,
+    height: (max.y - min.y + 1) as usize,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn library() -> UserLibrary {
+    let cells: CellSet = [(5, 5), (6, 5), (7, 5), (20, 20)]
+      .iter()
+      .map(|&(x, y)| Cell { x, y })
+      .collect();
+    let region = Some((Cell { x: 0, y: 0 }, Cell { x: 10, y: 10 }));
+    let blinker = term_from_cells("blinker".to_string(), String::new(), vec![], &cells, region);
+    UserLibrary {
+      patterns: vec![
+        UserPattern {
+          folder: String::new(),
+          term: blinker.clone(),
+        },
+        UserPattern {
+          folder: "oscillators".to_string(),
+          term: blinker,
+        },
+      ],
+    }
+  }
+
+  #[test]
+  fn term_from_cells_keeps_region_and_moves_it_to_origin() {
+    let term = &library().patterns[0].term;
+    assert_eq!((term.width, term.height), (3, 1));
+    assert_eq!(term.cells[0], Cell { x: 0, y: 0 });
+  }
+
+  #[test]
+  fn add_replaces_pattern_with_same_name_and_folder() {
+    let mut library = library();
+    let mut pattern = library.patterns[1].clone();
+    pattern.term.description = "Updated".to_string();
+    library.add(pattern);
+    assert_eq!(library.patterns.len(), 2);
+    assert_eq!(library.patterns[1].term.description, "Updated");
+  }
+
+  #[test]
+  fn library_round_trips_through_json_and_zip() {
+    let library = library();
+    assert_eq!(UserLibrary::from_json(&library.to_json()).unwrap(), library);
+    assert_eq!(UserLibrary::from_zip(&library.to_zip()).unwrap(), library);
+    assert_eq!(library.folders(), vec!["oscillators".to_string()]);
+  }
+}
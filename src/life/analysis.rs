@@ -0,0 +1,194 @@
+//! Analysis of the board: its cells split into objects, like the ash of a
+//! stabilised soup, each one named after the pattern of the lexicon it is, or
+//! else by its apgcode.
+
+use super::apgcode::{apgcode, period};
+use super::canonical::{canonical_form, Shape};
+use super::objects::separate;
+use super::{bounding_box, tick, CellSet};
+use crate::lexicon::{Cell, Lexicon};
+use std::collections::HashMap;
+
+/// Objects bigger than this aren't classified, as running them for a whole
+/// period on each generation would be too slow.
+pub const MAX_CLASSIFIED_CELLS: usize = 200;
+/// Shapes remembered by the classifier, forgotten all at once beyond that, as
+/// a chaotic pattern makes new ones at each generation.
+const MAX_KNOWN_SHAPES: usize = 10000;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Object {
+  /// Top-left and bottom-right cells.
+  pub bounds: (Cell, Cell),
+  pub population: usize,
+  /// Name of the pattern of the lexicon it is, in any phase or orientation.
+  pub name: Option<String>,
+  pub apgcode: Option<String>,
+}
+
+impl Object {
+  /// Name of the object shown on the board: its name in the lexicon, or else
+  /// its apgcode.
+  pub fn label(&self) -> Option<&str> {
+    self.name.as_deref().or(self.apgcode.as_deref())
+  }
+}
+
+/// Names objects, remembering the shapes it already named so that the same
+/// objects are named quickly from one generation to the next.
+pub struct Classifier {
+  /// Names of the patterns of the lexicon, by canonical form.
+  lexicon: HashMap<Shape, String>,
+  /// Name and apgcode of the shapes already seen, by canonical form.
+  known: HashMap<Shape, (Option<String>, Option<String>)>,
+}
+
+impl Classifier {
+  pub fn new(lexicon: &Lexicon) -> Self {
+    let mut names = HashMap::new();
+    for term in lexicon.terms.iter().filter(|term| !term.cells.is_empty()) {
+      let cells: CellSet = term.cells.iter().copied().collect();
+      // Several terms can show the same shape, e.g. "colour of a glider": the
+      // shortest name is the one of the pattern itself.
+      let name = names
+        .entry(canonical_form(&cells))
+        .or_insert_with(|| term.name.clone());
+      if term.name.len() < name.len() {
+        *name = term.name.clone();
+      }
+    }
+    Self {
+      lexicon: names,
+      known: HashMap::new(),
+    }
+  }
+
+  /// Name in the lexicon of any phase of the object, the shortest one if
+  /// several terms match.
+  fn lexicon_name(&self, cells: &CellSet) -> Option<String> {
+    let phases = match period(cells) {
+      Some((period, _)) => period,
+      None => 1,
+    };
+    let mut phase = cells.clone();
+    let mut names = vec![];
+    for _ in 0..phases {
+      names.extend(self.lexicon.get(&canonical_form(&phase)));
+      phase = tick(&phase);
+    }
+    names.into_iter().min_by_key(|name| name.len()).cloned()
+  }
+
+  /// Name and apgcode of the object, if it isn't too big to classify.
+  pub fn classify(&mut self, cells: &CellSet) -> (Option<String>, Option<String>) {
+    if cells.len() > MAX_CLASSIFIED_CELLS {
+      return (None, None);
+    }
+    let form = canonical_form(cells);
+    if let Some(known) = self.known.get(&form) {
+      return known.clone();
+    }
+    let classification = (self.lexicon_name(cells), apgcode(cells));
+    if self.known.len() >= MAX_KNOWN_SHAPES {
+      self.known.clear();
+    }
+    self.known.insert(form, classification.clone());
+    classification
+  }
+
+  /// Name in the lexicon of the whole pattern, wherever it is and however it
+  /// is rotated or reflected. Only its current phase is looked up when it is
+  /// too big to classify.
+  pub fn identify(&mut self, cells: &CellSet) -> Option<String> {
+    if cells.len() > MAX_CLASSIFIED_CELLS {
+      return self.lexicon.get(&canonical_form(cells)).cloned();
+    }
+    self.classify(cells).0
+  }
+
+  /// The cells split into objects, cells at most `distance` cells apart
+  /// belonging to the same object.
+  pub fn analyze(&mut self, cells: &CellSet, distance: i32) -> Vec<Object> {
+    separate(cells, distance)
+      .into_iter()
+      .filter_map(|object| {
+        let bounds = bounding_box(&object)?;
+        let (name, apgcode) = self.classify(&object);
+        Some(Object {
+          bounds,
+          population: object.len(),
+          name,
+          apgcode,
+        })
+      })
+      .collect()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::life::cells;
+
+  #[test]
+  fn objects_are_named_after_the_lexicon() {
+    let mut classifier = Classifier::new(Lexicon::get());
+    // A glider in another phase and orientation than in the lexicon, and a
+    // vertical blinker.
+    let board = cells(&[
+      (0, 0),
+      (2, 0),
+      (1, 1),
+      (2, 1),
+      (1, 2),
+      (20, 0),
+      (20, 1),
+      (20, 2),
+    ]);
+    let mut objects = classifier.analyze(&board, 2);
+    objects.sort_by_key(|object| object.bounds.0.x);
+    assert_eq!(objects.len(), 2);
+    assert_eq!(objects[0].name.as_deref(), Some("glider"));
+    assert_eq!(objects[0].apgcode.as_deref(), Some("xq4_153"));
+    assert_eq!(
+      objects[0].bounds,
+      (Cell { x: 0, y: 0 }, Cell { x: 2, y: 2 })
+    );
+    assert_eq!(objects[1].label(), Some("blinker"));
+    // With a larger distance, both are the same object, which isn't periodic.
+    let objects = classifier.analyze(&board, 20);
+    assert_eq!(objects.len(), 1);
+    assert_eq!(objects[0].label(), None);
+  }
+
+  #[test]
+  fn board_is_identified_in_any_phase() {
+    let lexicon = Lexicon::get();
+    let mut classifier = Classifier::new(lexicon);
+    // The pulsar of the lexicon, moved, and in its other phases.
+    let term = lexicon.get_term("pulsar".to_string()).unwrap();
+    let mut pulsar: CellSet = term
+      .cells
+      .iter()
+      .map(|cell| Cell {
+        x: cell.x + 7,
+        y: cell.y - 3,
+      })
+      .collect();
+    for _ in 0..3 {
+      assert_eq!(classifier.identify(&pulsar).as_deref(), Some("pulsar"));
+      pulsar = tick(&pulsar);
+    }
+    let glider_and_blinker = cells(&[
+      (0, 0),
+      (2, 0),
+      (1, 1),
+      (2, 1),
+      (1, 2),
+      (20, 0),
+      (20, 1),
+      (20, 2),
+    ]);
+    assert_eq!(classifier.identify(&glider_and_blinker), None);
+  }
+}
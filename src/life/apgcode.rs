@@ -0,0 +1,293 @@
+//! apgcodes, naming objects like apgsearch and Catagolue do: `xs4_33` is the
+//! block (a still life of 4 cells), `xp2_7` the blinker (an oscillator of
+//! period 2) and `xq4_153` the glider (a spaceship of period 4). Codes are
+//! encoded from the canonical phase and orientation of the object, and can be
+//! decoded back to its cells.
+
+use super::{bounding_box, tick, CellSet};
+use crate::lexicon::Cell;
+
+/// Digits of the extended Wechsler format, each one a column of 5 cells.
+const DIGITS: &[u8] = b"0123456789abcdefghijklmnopqrstuv";
+/// Digits following `y` in the extended Wechsler format, for runs of 4 to 39
+/// zeros.
+const RUN_DIGITS: &[u8] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+/// Objects not coming back to their first phase within this many generations
+/// aren't identified.
+pub const MAX_PERIOD: u32 = 64;
+
+/// The cells moved so that their bounding box starts at the origin.
+pub fn normalize(cells: &CellSet) -> CellSet {
+  match bounding_box(cells) {
+    Some((min, _)) => cells
+      .iter()
+      .map(|cell| Cell {
+        x: cell.x - min.x,
+        y: cell.y - min.y,
+      })
+      .collect(),
+    None => CellSet::new(),
+  }
+}
+
+/// The 8 orientations of the cells: rotations and reflections.
+pub fn orientations(cells: &CellSet) -> Vec<CellSet> {
+  let transforms: [fn(Cell) -> Cell; 8] = [
+    |c| Cell { x: c.x, y: c.y },
+    |c| Cell { x: -c.y, y: c.x },
+    |c| Cell { x: -c.x, y: -c.y },
+    |c| Cell { x: c.y, y: -c.x },
+    |c| Cell { x: -c.x, y: c.y },
+    |c| Cell { x: c.x, y: -c.y },
+    |c| Cell { x: c.y, y: c.x },
+    |c| Cell { x: -c.y, y: -c.x },
+  ];
+  transforms
+    .iter()
+    .map(|transform| cells.iter().map(|&cell| transform(cell)).collect())
+    .collect()
+}
+
+/// Writes `count` zeros the way the extended Wechsler format shortens them:
+/// `w` for 2, `x` for 3, and `y` followed by a digit from `0` to `z` for 4 to
+/// 39.
+fn push_zeros(code: &mut String, mut count: usize) {
+  while count >= 4 {
+    let run = count.min(4 + RUN_DIGITS.len() - 1);
+    code.push('y');
+    code.push(RUN_DIGITS[run - 4] as char);
+    count -= run;
+  }
+  match count {
+    3 => code.push('x'),
+    2 => code.push('w'),
+    1 => code.push('0'),
+    _ => {}
+  }
+}
+
+/// Extended Wechsler format of the cells, in their current orientation. Rows
+/// are read in strips of 5, separated by `z`, each column of a strip being a
+/// digit whose bits are its cells from top to bottom.
+pub fn wechsler(cells: &CellSet) -> String {
+  let cells = normalize(cells);
+  let (_, max) = match bounding_box(&cells) {
+    Some(bounds) => bounds,
+    None => return String::new(),
+  };
+  let strips: Vec<String> = (0..=max.y / 5)
+    .map(|strip| {
+      let digits: Vec<usize> = (0..=max.x)
+        .map(|x| {
+          (0..5)
+            .filter(|row| {
+              cells.contains(&Cell {
+                x,
+                y: strip * 5 + row,
+              })
+            })
+            .map(|row| 1 << row)
+            .sum()
+        })
+        .collect();
+      let len = digits.iter().rposition(|&d| d != 0).map_or(0, |i| i + 1);
+      let mut code = String::new();
+      let mut zeros = 0;
+      for &digit in &digits[..len] {
+        if digit == 0 {
+          zeros += 1;
+        } else {
+          push_zeros(&mut code, zeros);
+          zeros = 0;
+          code.push(DIGITS[digit] as char);
+        }
+      }
+      code
+    })
+    .collect();
+  strips.join("z")
+}
+
+/// Shortest code, or the first in alphabetical order among the shortest, as
+/// apgsearch picks the canonical one.
+fn canonical<I: IntoIterator<Item = String>>(codes: I) -> Option<String> {
+  codes
+    .into_iter()
+    .min_by(|a, b| a.len().cmp(&b.len()).then_with(|| a.cmp(b)))
+}
+
+/// Period of the object and how much it moved during a period, if it comes
+/// back to its first phase within `MAX_PERIOD` generations.
+pub fn period(cells: &CellSet) -> Option<(u32, (i32, i32))> {
+  let (start, _) = bounding_box(cells)?;
+  let shape = normalize(cells);
+  let mut current = cells.clone();
+  for generation in 1..=MAX_PERIOD {
+    current = tick(&current);
+    let (min, _) = bounding_box(&current)?;
+    if current.len() == cells.len() && normalize(&current) == shape {
+      return Some((generation, (min.x - start.x, min.y - start.y)));
+    }
+  }
+  None
+}
+
+/// apgcode of an object: `xs` and its population for a still life, `xp` and
+/// its period for an oscillator, `xq` and its period for a spaceship, followed
+/// by the canonical code among all its phases and orientations. `None` when
+/// the object isn't periodic, or dies out.
+pub fn apgcode(cells: &CellSet) -> Option<String> {
+  let (period, displacement) = period(cells)?;
+  let mut phases = vec![cells.clone()];
+  for _ in 1..period {
+    let next = tick(phases.last().unwrap());
+    phases.push(next);
+  }
+  let code = canonical(
+    phases
+      .iter()
+      .flat_map(orientations)
+      .map(|orientation| wechsler(&orientation)),
+  )?;
+  let prefix = match (period, displacement) {
+    (1, _) => format!("xs{}", cells.len()),
+    (_, (0, 0)) => format!("xp{}", period),
+    _ => format!("xq{}", period),
+  };
+  Some(format!("{}_{}", prefix, code))
+}
+
+/// Cells of an apgcode, e.g. `xq4_153`, or of an extended Wechsler code alone,
+/// e.g. `153`. Only still lifes, oscillators and spaceships can be decoded,
+/// other apgcodes (like `yl` ones for growing patterns) not having the cells
+/// in them.
+pub fn decode(code: &str) -> Result<CellSet, String> {
+  let code = code.trim();
+  let wechsler = match code.split_once('_') {
+    Some((prefix, wechsler)) => {
+      let period = prefix
+        .strip_prefix("xs")
+        .or_else(|| prefix.strip_prefix("xp"))
+        .or_else(|| prefix.strip_prefix("xq"));
+      match period {
+        Some(period) if period.parse::<u32>().is_ok() => wechsler,
+        _ => {
+          return Err(format!(
+            "“{}” isn't the apgcode of a still life, an oscillator or a spaceship",
+            code
+          ))
+        }
+      }
+    }
+    None => code,
+  };
+  let digit = |c: char| DIGITS.iter().position(|&d| d as char == c);
+  let run_digit = |c: char| RUN_DIGITS.iter().position(|&d| d as char == c);
+  let mut cells = CellSet::new();
+  let (mut x, mut strip) = (0, 0);
+  let mut chars = wechsler.chars();
+  while let Some(c) = chars.next() {
+    match c {
+      'w' => x += 2,
+      'x' => x += 3,
+      'y' => match chars.next().and_then(run_digit) {
+        Some(zeros) => x += 4 + zeros as i32,
+        None => return Err(format!("“y” must be followed by a digit in “{}”", code)),
+      },
+      'z' => {
+        x = 0;
+        strip += 1;
+      }
+      c => {
+        let column = digit(c).ok_or(format!("unexpected character “{}” in “{}”", c, code))?;
+        for row in 0..5 {
+          if column & (1 << row) != 0 {
+            cells.insert(Cell {
+              x,
+              y: strip * 5 + row,
+            });
+          }
+        }
+        x += 1;
+      }
+    }
+  }
+  if cells.is_empty() {
+    return Err(format!("“{}” has no cells", code));
+  }
+  Ok(cells)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::life::cells;
+
+  /// Cells of a pattern drawn with `o` for live cells, one row per line.
+  fn pattern(rows: &str) -> CellSet {
+    let coords: Vec<(i32, i32)> = rows
+      .lines()
+      .enumerate()
+      .flat_map(|(y, row)| {
+        row
+          .chars()
+          .enumerate()
+          .filter(|&(_, c)| c == 'o')
+          .map(move |(x, _)| (x as i32, y as i32))
+      })
+      .collect();
+    cells(&coords)
+  }
+
+  #[test]
+  fn wechsler_shortens_zeros() {
+    let cells = pattern("o......o\n\n\n\n\no");
+    assert_eq!(wechsler(&cells), "1y21z1");
+  }
+
+  #[test]
+  fn identifies_common_objects() {
+    let objects = [
+      ("oo\noo", "xs4_33"),
+      (".oo.\no..o\n.oo.", "xs6_696"),
+      (".oo.\no..o\n.o.o\n..o.", "xs7_2596"),
+      ("oo.\no.o\n.o.", "xs5_253"),
+      ("ooo", "xp2_7"),
+      (".ooo\nooo.", "xp2_7e"),
+      ("oo..\noo..\n..oo\n..oo", "xp2_318c"),
+      (".o.\n..o\nooo", "xq4_153"),
+      (".o..o\no....\no...o\noooo.", "xq4_6frc"),
+    ];
+    for (rows, code) in objects {
+      assert_eq!(apgcode(&pattern(rows)).as_deref(), Some(code), "{}", rows);
+    }
+    assert_eq!(apgcode(&pattern("o")), None);
+  }
+
+  #[test]
+  fn decodes_to_the_same_object() {
+    for code in ["xs4_33", "xp2_318c", "xq4_6frc", "xs12_g8o653z11"] {
+      let cells = decode(code).unwrap();
+      assert_eq!(apgcode(&cells).as_deref(), Some(code));
+    }
+    assert_eq!(
+      decode("1y21z1").map(|cells| wechsler(&cells)),
+      Ok("1y21z1".to_string())
+    );
+    // Runs of 36 to 39 zeros, and longer ones.
+    for (code, gap) in [("1yw1", 36), ("1yz1", 39), ("1yz01", 40)] {
+      let cells = decode(code).unwrap();
+      assert!(cells.contains(&Cell { x: gap + 1, y: 0 }));
+      assert_eq!(wechsler(&cells), code);
+    }
+    // These 4 cells don't come back to their first phase: no apgcode.
+    assert_eq!(apgcode(&decode("xq4_27").unwrap()), None);
+    // Any phase and orientation of the glider is encoded the same.
+    assert_eq!(
+      apgcode(&decode("xq4_351").unwrap()).as_deref(),
+      Some("xq4_153")
+    );
+    assert!(decode("yl144_1_16_afb5f3db909e60548f086e22ee3353ac").is_err());
+    assert!(decode("xs4_3!").is_err());
+  }
+}
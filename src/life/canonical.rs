@@ -0,0 +1,62 @@
+//! Canonical form of a pattern, the same wherever it is and however it is
+//! rotated or reflected, to recognise the same pattern in another position.
+
+use super::apgcode::{normalize, orientations};
+use super::CellSet;
+use crate::hash::fnv1a;
+
+/// Live cells as coordinates from the top-left corner of the bounding box,
+/// sorted by row then column.
+pub type Shape = Vec<(i32, i32)>;
+
+/// The cells moved to the origin and sorted, in their current orientation.
+pub fn shape(cells: &CellSet) -> Shape {
+  let mut shape: Shape = normalize(cells)
+    .iter()
+    .map(|cell| (cell.x, cell.y))
+    .collect();
+  shape.sort_unstable_by_key(|&(x, y)| (y, x));
+  shape
+}
+
+/// The smallest shape among the 8 orientations of the cells (rotations and
+/// reflections).
+pub fn canonical_form(cells: &CellSet) -> Shape {
+  orientations(cells)
+    .iter()
+    .map(shape)
+    .min()
+    .unwrap_or_default()
+}
+
+/// Hash of the canonical form, to identify the pattern where it is stored or
+/// shared.
+pub fn canonical_hash(cells: &CellSet) -> u64 {
+  fnv1a(
+    canonical_form(cells)
+      .iter()
+      .flat_map(|&(x, y)| x.to_le_bytes().into_iter().chain(y.to_le_bytes())),
+  )
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::life::cells;
+
+  #[test]
+  fn same_pattern_in_any_position_and_orientation() {
+    let glider = cells(&[(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)]);
+    // Moved, rotated by 90 degrees and reflected.
+    let rotated = cells(&[(12, 11), (11, 12), (10, 10), (10, 11), (10, 12)]);
+    let reflected = cells(&[(-1, 0), (-2, 1), (0, 2), (-1, 2), (-2, 2)]);
+    assert_eq!(canonical_form(&glider), canonical_form(&rotated));
+    assert_eq!(canonical_hash(&glider), canonical_hash(&rotated));
+    assert_eq!(canonical_hash(&glider), canonical_hash(&reflected));
+    let block = cells(&[(0, 0), (1, 0), (0, 1), (1, 1)]);
+    assert_ne!(canonical_hash(&glider), canonical_hash(&block));
+    assert_eq!(canonical_form(&block), vec![(0, 0), (1, 0), (0, 1), (1, 1)]);
+    // Hashes must not change, as they may have been stored.
+    assert_eq!(canonical_hash(&block), 0x5c1d_cfdc_3635_b665);
+  }
+}
@@ -1,6 +1,11 @@
-use lexicon::Cell;
+use crate::lexicon::{Cell, Term};
 use std::collections::HashSet;
 
+pub mod analysis;
+pub mod apgcode;
+pub mod canonical;
+pub mod objects;
+
 pub type CellSet = HashSet<Cell>;
 
 fn singleton(cell: Cell) -> CellSet {
@@ -72,3 +77,30 @@ fn cell_neighbors(cell: Cell) -> Vec<Cell> {
     })
     .collect()
 }
+
+pub fn bounding_box(cells: &CellSet) -> Option<(Cell, Cell)> {
+  let mut iter = cells.iter();
+  let first = *iter.next()?;
+  Some(iter.fold((first, first), |(min, max), cell| {
+    (
+      Cell {
+        x: min.x.min(cell.x),
+        y: min.y.min(cell.y),
+      },
+      Cell {
+        x: max.x.max(cell.x),
+        y: max.y.max(cell.y),
+      },
+    )
+  }))
+}
+
+pub fn from_term(term: &Term) -> CellSet {
+  term.cells.iter().copied().collect()
+}
+
+/// The cells at the given coordinates, to write patterns in tests.
+#[cfg(test)]
+pub(crate) fn cells(coords: &[(i32, i32)]) -> CellSet {
+  coords.iter().map(|&(x, y)| Cell { x, y }).collect()
+}
@@ -0,0 +1,156 @@
+use super::{bounding_box, CellSet};
+use crate::lexicon::Cell;
+
+/// Cells connected to `cell` (which doesn't need to be alive itself), two
+/// cells being connected when they are at most `distance` cells apart in both
+/// directions.
+pub fn object_at(cells: &CellSet, cell: Cell, distance: i32) -> CellSet {
+  let seeds = cells_near(cells, cell, distance);
+  connected_cells(cells, seeds, distance)
+}
+
+/// Splits the cells into objects, each made of connected cells.
+pub fn separate(cells: &CellSet, distance: i32) -> Vec<CellSet> {
+  let mut remaining = cells.clone();
+  let mut objects = vec![];
+  while let Some(&cell) = remaining.iter().next() {
+    let object = connected_cells(&remaining, vec![cell], distance);
+    remaining.retain(|cell| !object.contains(cell));
+    objects.push(object);
+  }
+  objects
+}
+
+/// Follows an object over `gens` generations: the object in `cells` is made
+/// of the cells close to the `previous` object, and of the cells connected to
+/// them. As nothing moves faster than light, one cell per generation, the
+/// search is widened by `gens` cells.
+pub fn track_object(previous: &CellSet, cells: &CellSet, distance: i32, gens: u32) -> CellSet {
+  let (min, max) = match bounding_box(previous) {
+    Some(bounds) => bounds,
+    None => return CellSet::new(),
+  };
+  let reach = distance.saturating_add(gens.min(i32::MAX as u32) as i32);
+  let seeds: Vec<Cell> = cells
+    .iter()
+    .copied()
+    .filter(|cell| {
+      cell.x >= min.x.saturating_sub(reach)
+        && cell.x <= max.x.saturating_add(reach)
+        && cell.y >= min.y.saturating_sub(reach)
+        && cell.y <= max.y.saturating_add(reach)
+        && previous.iter().any(|p| near(*p, *cell, reach))
+    })
+    .collect();
+  connected_cells(cells, seeds, distance)
+}
+
+/// Average position of the cells, in cells.
+pub fn centroid(cells: &CellSet) -> Option<(f64, f64)> {
+  if cells.is_empty() {
+    return None;
+  }
+  let (sum_x, sum_y) = cells.iter().fold((0.0, 0.0), |(x, y), cell| {
+    (x + cell.x as f64, y + cell.y as f64)
+  });
+  let count = cells.len() as f64;
+  Some((sum_x / count, sum_y / count))
+}
+
+fn near(a: Cell, b: Cell, distance: i32) -> bool {
+  (a.x - b.x).abs() <= distance && (a.y - b.y).abs() <= distance
+}
+
+fn cells_near(cells: &CellSet, cell: Cell, distance: i32) -> Vec<Cell> {
+  let mut near_cells = vec![];
+  for dx in -distance..=distance {
+    for dy in -distance..=distance {
+      let neighbor = Cell {
+        x: cell.x + dx,
+        y: cell.y + dy,
+      };
+      if cells.contains(&neighbor) {
+        near_cells.push(neighbor);
+      }
+    }
+  }
+  near_cells
+}
+
+fn connected_cells(cells: &CellSet, seeds: Vec<Cell>, distance: i32) -> CellSet {
+  let mut object = CellSet::new();
+  let mut to_visit = seeds;
+  while let Some(cell) = to_visit.pop() {
+    if object.insert(cell) {
+      to_visit.extend(
+        cells_near(cells, cell, distance)
+          .into_iter()
+          .filter(|neighbor| !object.contains(neighbor)),
+      );
+    }
+  }
+  object
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::life::{cells, tick};
+
+  fn glider_and_block() -> CellSet {
+    cells(&[
+      (1, 0),
+      (2, 1),
+      (0, 2),
+      (1, 2),
+      (2, 2),
+      (20, 20),
+      (21, 20),
+      (20, 21),
+      (21, 21),
+    ])
+  }
+
+  #[test]
+  fn object_at_returns_connected_cells_only() {
+    let object = object_at(&glider_and_block(), Cell { x: 0, y: 0 }, 2);
+    assert_eq!(object.len(), 5);
+    assert!(object_at(&glider_and_block(), Cell { x: 10, y: 10 }, 2).is_empty());
+  }
+
+  #[test]
+  fn separate_splits_into_objects() {
+    let mut objects = separate(&glider_and_block(), 2);
+    objects.sort_by_key(|object| object.len());
+    assert_eq!(
+      objects
+        .iter()
+        .map(|object| object.len())
+        .collect::<Vec<_>>(),
+      vec![4, 5]
+    );
+  }
+
+  #[test]
+  fn track_object_follows_glider() {
+    let mut cells = glider_and_block();
+    let mut glider = object_at(&cells, Cell { x: 1, y: 1 }, 2);
+    for _ in 0..8 {
+      cells = tick(&cells);
+      glider = track_object(&glider, &cells, 2, 1);
+    }
+    assert_eq!(glider.len(), 5);
+    assert_eq!(centroid(&glider), Some((3.2, 3.4)));
+  }
+
+  #[test]
+  fn track_object_follows_glider_over_skipped_generations() {
+    let glider = cells(&[(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)]);
+    let mut cells = glider.clone();
+    for _ in 0..32 {
+      cells = tick(&cells);
+    }
+    assert!(track_object(&glider, &cells, 2, 1).is_empty());
+    assert_eq!(track_object(&glider, &cells, 2, 32), cells);
+  }
+}
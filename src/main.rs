@@ -1,21 +1,4 @@
-use yew::prelude::*;
-
-mod color_utils;
-mod components;
-mod life;
-mod settings;
-
-use components::game::Game;
-use settings::{default_settings, Settings};
-
-#[function_component(App)]
-fn app() -> Html {
-  html! {
-    <ContextProvider<Settings> context={default_settings()}>
-      <Game/>
-    </ContextProvider<Settings>>
-  }
-}
+use yew_app::App;
 
 fn main() {
   yew::start_app::<App>();
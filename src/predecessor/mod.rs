@@ -0,0 +1,251 @@
+//! Search for a predecessor of a pattern: a parent generation evolving into it.
+//! The rule of each cell of the target is encoded as clauses on the cells of
+//! the parent around it, given to the SAT solver, which either finds a parent
+//! or proves that none exists.
+
+pub mod worker;
+
+use crate::lexicon::Cell;
+use crate::life::CellSet;
+use crate::sat::{Lit, Outcome, Solver, Var};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Regions bigger than this (in cells) aren't searched, as the problem grows
+/// with their area.
+pub const MAX_REGION_CELLS: i64 = 40 * 40;
+
+const NEIGHBORS: [(i32, i32); 8] = [
+  (-1, -1),
+  (0, -1),
+  (1, -1),
+  (-1, 0),
+  (1, 0),
+  (-1, 1),
+  (0, 1),
+  (1, 1),
+];
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Predecessor {
+  Parent(CellSet),
+  /// No parent gives the target in the region, whatever is around it: the
+  /// target is a Garden of Eden.
+  GardenOfEden,
+  /// No parent within a cell of the region evolves exactly into the target,
+  /// with nothing else around.
+  NoneInRegion,
+}
+
+/// Number of cells of the region, top-left and bottom-right cells.
+pub fn region_cells((min, max): (Cell, Cell)) -> i64 {
+  (max.x - min.x + 1) as i64 * (max.y - min.y + 1) as i64
+}
+
+fn grow((min, max): (Cell, Cell), margin: i32) -> (Cell, Cell) {
+  (
+    Cell {
+      x: min.x - margin,
+      y: min.y - margin,
+    },
+    Cell {
+      x: max.x + margin,
+      y: max.y + margin,
+    },
+  )
+}
+
+fn cells_of((min, max): (Cell, Cell)) -> impl Iterator<Item = Cell> {
+  (min.y..=max.y).flat_map(move |y| (min.x..=max.x).map(move |x| Cell { x, y }))
+}
+
+fn contains((min, max): (Cell, Cell), cell: Cell) -> bool {
+  (min.x..=max.x).contains(&cell.x) && (min.y..=max.y).contains(&cell.y)
+}
+
+/// Adds the clause of the literals, each one a variable of the parent and
+/// whether the cell is alive in it. Cells without a variable are dead.
+fn add_clause(solver: &mut Solver, lits: impl IntoIterator<Item = (Option<Var>, bool)>) {
+  let mut clause = vec![];
+  for (var, alive) in lits {
+    match (var, alive) {
+      (Some(var), true) => clause.push(Lit::positive(var)),
+      (Some(var), false) => clause.push(Lit::negative(var)),
+      (None, true) => {}
+      // Always true: a dead cell isn't alive.
+      (None, false) => return,
+    }
+  }
+  solver.add_clause(&clause);
+}
+
+/// A search for a parent of the target, which can be run a bit at a time.
+pub struct Search {
+  solver: Solver,
+  /// Cells of the parent that may be alive, by variable.
+  cells: Vec<Cell>,
+  isolated: bool,
+}
+
+impl Search {
+  /// Searches for a parent of the cells of `target` in `region` (the cells of
+  /// the region not in `target` being dead), with cells alive only within one
+  /// cell of the region, which are the ones changing it. If `isolated`, the
+  /// parent must evolve exactly into the target, with nothing around it;
+  /// otherwise anything may appear around the region, and when there is no
+  /// parent the target is a Garden of Eden.
+  pub fn new(target: &CellSet, region: (Cell, Cell), isolated: bool) -> Self {
+    let mut solver = Solver::new();
+    let cells: Vec<Cell> = cells_of(grow(region, 1)).collect();
+    let vars: HashMap<Cell, Var> = cells.iter().map(|&cell| (cell, solver.new_var())).collect();
+    // Cells further away have no neighbor alive in the parent, so stay dead.
+    let constrained = if isolated { grow(region, 2) } else { region };
+    for cell in cells_of(constrained) {
+      let alive = contains(region, cell) && target.contains(&cell);
+      let center = vars.get(&cell).copied();
+      let neighbors: Vec<Option<Var>> = NEIGHBORS
+        .iter()
+        .map(|&(dx, dy)| {
+          vars
+            .get(&Cell {
+              x: cell.x + dx,
+              y: cell.y + dy,
+            })
+            .copied()
+        })
+        .collect();
+      let neighbors = &neighbors;
+      for mask in 0..256_u32 {
+        let count = mask.count_ones();
+        // The neighbors in the mask alive, and the others.
+        let chosen = |alive| {
+          (0..8)
+            .filter(move |i| mask & (1 << i) != 0)
+            .map(move |i| (neighbors[i], alive))
+        };
+        let others = (0..8)
+          .filter(|i| mask & (1 << i) == 0)
+          .map(|i| (neighbors[i], true));
+        match (alive, count) {
+          // At most 3 neighbors alive.
+          (true, 4) => add_clause(&mut solver, chosen(false)),
+          // At least 2 neighbors alive: one of any 7.
+          (true, 7) => add_clause(&mut solver, chosen(true)),
+          // With exactly 2 neighbors alive, it was alive.
+          (true, 2) => add_clause(
+            &mut solver,
+            chosen(false).chain(others).chain([(center, true)]),
+          ),
+          // Not exactly 3 neighbors alive.
+          (false, 3) => add_clause(&mut solver, chosen(false).chain(others)),
+          // With exactly 2 neighbors alive, it was dead.
+          (false, 2) => add_clause(
+            &mut solver,
+            chosen(false).chain(others).chain([(center, false)]),
+          ),
+          _ => {}
+        }
+      }
+    }
+    Self {
+      solver,
+      cells,
+      isolated,
+    }
+  }
+
+  /// Conflicts met so far, showing how hard the search is.
+  pub fn conflicts(&self) -> u64 {
+    self.solver.conflicts()
+  }
+
+  /// Runs the search for at most `max_conflicts` conflicts, returning its
+  /// result if it is over.
+  pub fn run(&mut self, max_conflicts: u64) -> Option<Predecessor> {
+    Some(match self.solver.solve(max_conflicts)? {
+      Outcome::Satisfiable(model) => Predecessor::Parent(
+        self
+          .cells
+          .iter()
+          .zip(model)
+          .filter(|&(_, alive)| alive)
+          .map(|(&cell, _)| cell)
+          .collect(),
+      ),
+      Outcome::Unsatisfiable if self.isolated => Predecessor::NoneInRegion,
+      Outcome::Unsatisfiable => Predecessor::GardenOfEden,
+    })
+  }
+}
+
+/// Searches for a parent of the target until the search is over.
+pub fn find_predecessor(target: &CellSet, region: (Cell, Cell), isolated: bool) -> Predecessor {
+  let mut search = Search::new(target, region, isolated);
+  loop {
+    if let Some(predecessor) = search.run(u64::MAX) {
+      return predecessor;
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::life::{cells, tick};
+
+  fn in_region(cells: &CellSet, region: (Cell, Cell)) -> CellSet {
+    cells
+      .iter()
+      .copied()
+      .filter(|&cell| contains(region, cell))
+      .collect()
+  }
+
+  #[test]
+  fn parents_evolve_into_the_target() {
+    let glider = cells(&[(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)]);
+    let region = (Cell { x: -1, y: -1 }, Cell { x: 3, y: 3 });
+    match find_predecessor(&glider, region, true) {
+      Predecessor::Parent(parent) => assert_eq!(tick(&parent), glider),
+      predecessor => panic!("expected a parent, got {:?}", predecessor),
+    }
+    // Not isolated, only the region must match.
+    let blinker = cells(&[(0, 0), (1, 0), (2, 0)]);
+    let region = (Cell { x: 0, y: -1 }, Cell { x: 2, y: 1 });
+    match find_predecessor(&blinker, region, false) {
+      Predecessor::Parent(parent) => assert_eq!(in_region(&tick(&parent), region), blinker),
+      predecessor => panic!("expected a parent, got {:?}", predecessor),
+    }
+  }
+
+  #[test]
+  fn same_results_as_trying_all_parents() {
+    // Every parent within a cell of a 2×1 region, and what they evolve into.
+    let region = (Cell { x: 0, y: 0 }, Cell { x: 1, y: 0 });
+    let parent_cells: Vec<Cell> = cells_of(grow(region, 1)).collect();
+    let mut children = vec![];
+    for parent in 0..1_u32 << parent_cells.len() {
+      let parent: CellSet = (0..parent_cells.len())
+        .filter(|i| parent & (1 << i) != 0)
+        .map(|i| parent_cells[i])
+        .collect();
+      children.push(tick(&parent));
+    }
+    let region_cells: Vec<Cell> = cells_of(region).collect();
+    for target in 0..1_u32 << region_cells.len() {
+      let target: CellSet = (0..region_cells.len())
+        .filter(|i| target & (1 << i) != 0)
+        .map(|i| region_cells[i])
+        .collect();
+      let exists = children.contains(&target);
+      match find_predecessor(&target, region, true) {
+        Predecessor::Parent(parent) => assert_eq!(tick(&parent), target),
+        Predecessor::NoneInRegion => assert!(!exists, "{:?} has a parent", target),
+        Predecessor::GardenOfEden => panic!("isolated searches give no Garden of Eden"),
+      }
+      // Any 2×1 pattern has a parent.
+      let predecessor = find_predecessor(&target, region, false);
+      assert!(matches!(predecessor, Predecessor::Parent(_)));
+    }
+  }
+}
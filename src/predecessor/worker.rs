@@ -0,0 +1,55 @@
+//! Predecessor search run in a Web Worker, a few conflicts at a time (see
+//! `batch`), so that long searches can be stopped.
+
+use super::{Predecessor, Search};
+use crate::batch::{self, BatchWorker, Job};
+use crate::lexicon::Cell;
+use crate::life::CellSet;
+use serde::{Deserialize, Serialize};
+
+/// Path of the script of the worker, built by Trunk from
+/// `src/bin/predecessor-worker.rs`.
+pub const PREDECESSOR_WORKER_PATH: &str = "/predecessor-worker.js";
+/// Conflicts between two checks of the time spent in a batch.
+const CONFLICTS_PER_CHECK: u64 = 200;
+
+pub type PredecessorWorker = BatchWorker<Search>;
+pub type Input = batch::Input<Start>;
+
+/// Starts searching for a parent of the cells of `target` in `region`, see
+/// `Search::new`.
+#[derive(Serialize, Deserialize)]
+pub struct Start {
+  pub target: CellSet,
+  pub region: (Cell, Cell),
+  pub isolated: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Progress {
+  pub conflicts: u64,
+  /// Result of the search, once it is over.
+  pub predecessor: Option<Predecessor>,
+}
+
+impl Job for Search {
+  type Start = Start;
+  type Progress = Progress;
+
+  fn start(start: Start) -> Self {
+    Search::new(&start.target, start.region, start.isolated)
+  }
+
+  fn run_batch(&mut self, in_time: impl Fn() -> bool) -> (Progress, bool) {
+    let mut predecessor = None;
+    while predecessor.is_none() && in_time() {
+      predecessor = self.run(CONFLICTS_PER_CHECK);
+    }
+    let done = predecessor.is_some();
+    let progress = Progress {
+      conflicts: self.conflicts(),
+      predecessor,
+    };
+    (progress, done)
+  }
+}
@@ -0,0 +1,156 @@
+use super::color_mode::Layers;
+use super::lod::{density_buckets, shade_color, use_lod};
+use super::{cell_range, snap_grid_line};
+use crate::color_utils::{to_hex, Rgb};
+use crate::export::RgbaImage;
+use crate::lexicon::Cell;
+use crate::life::CellSet;
+use crate::settings::Settings;
+use crate::theme::Theme;
+use crate::viewport::{grid_width, pitch};
+use wasm_bindgen::{Clamped, JsValue};
+use web_sys::{CanvasRenderingContext2d, ImageData};
+
+/// Draws the board with one `fill_rect` per grid line and per cell: grid
+/// first, then each layer of cells in turn. When zoomed too far out to draw
+/// cells one by one, only the live `cells` are drawn. `tests/render.rs` checks
+/// `pixels::draw_board` against it.
+#[allow(clippy::too_many_arguments)]
+pub fn draw_board(
+  context: &CanvasRenderingContext2d,
+  (width, height): (u32, u32),
+  cells: &CellSet,
+  layers: &Layers,
+  offset: (f64, f64),
+  zoom: f64,
+  settings: &Settings,
+  theme: &Theme,
+) {
+  context.set_fill_style(&JsValue::from(to_hex(theme.background)));
+  context.fill_rect(0.0, 0.0, width.into(), height.into());
+
+  if use_lod(pitch(zoom, settings)) {
+    draw_density(
+      context,
+      (width, height),
+      cells,
+      offset,
+      zoom,
+      settings,
+      theme,
+    );
+    return;
+  }
+  // Grid lines are at least a pixel wide, so a width of 0 hides the grid.
+  if zoom > settings.grid_min_zoom && settings.grid_width > 0.0 {
+    draw_grid(context, (width, height), offset, zoom, settings, theme.grid);
+  }
+  for (color, cells) in layers {
+    draw_cells(
+      context,
+      (width, height),
+      cells,
+      to_hex(*color),
+      offset,
+      zoom,
+      settings,
+    );
+  }
+}
+
+/// Copies an image drawn in memory to the top-left corner of the canvas.
+pub fn put_image(context: &CanvasRenderingContext2d, image: &RgbaImage) {
+  // Image data can't be empty.
+  if image.width == 0 || image.height == 0 {
+    return;
+  }
+  let image_data =
+    ImageData::new_with_u8_clamped_array_and_sh(Clamped(&image.pixels), image.width, image.height)
+      .unwrap();
+  context.put_image_data(&image_data, 0.0, 0.0).unwrap();
+}
+
+fn draw_grid(
+  context: &CanvasRenderingContext2d,
+  (width, height): (u32, u32),
+  offset: (f64, f64),
+  zoom: f64,
+  settings: &Settings,
+  color: Rgb,
+) {
+  context.set_fill_style(&JsValue::from(to_hex(color)));
+
+  let (cell_range_x, cell_range_y) = cell_range(settings, offset, zoom, (width, height));
+  for i in cell_range_x {
+    let (x, line_width) = snap_grid_line(
+      offset.0 + i as f64 * pitch(zoom, settings),
+      settings.grid_width,
+    );
+    context.fill_rect(x, 0.0, line_width, height.into())
+  }
+
+  for j in cell_range_y {
+    let (y, line_height) = snap_grid_line(
+      offset.1 + j as f64 * pitch(zoom, settings),
+      settings.grid_width,
+    );
+    context.fill_rect(0.0, y, width.into(), line_height)
+  }
+}
+
+fn draw_cells(
+  context: &CanvasRenderingContext2d,
+  size: (u32, u32),
+  cells: &[Cell],
+  color: String,
+  offset: (f64, f64),
+  zoom: f64,
+  settings: &Settings,
+) {
+  context.set_fill_style(&JsValue::from(color));
+
+  let (cell_range_x, cell_range_y) = cell_range(settings, offset, zoom, size);
+  let cells = cells.iter().filter(|Cell { x, y }| {
+    *x >= cell_range_x.start
+      && *x <= cell_range_x.end
+      && *y >= cell_range_y.start
+      && *y <= cell_range_y.end
+  });
+
+  let (pitch, grid_width) = (pitch(zoom, settings), grid_width(zoom, settings));
+  for cell in cells {
+    context.fill_rect(
+      offset.0 + (grid_width + pitch * cell.x as f64),
+      offset.1 + (grid_width + pitch * cell.y as f64),
+      zoom * settings.cell_size,
+      zoom * settings.cell_size,
+    );
+  }
+}
+
+/// Draws live cells when they are smaller than a pixel, by shading each pixel
+/// according to how many of its cells are alive. Trails aren't drawn at this
+/// level of detail.
+fn draw_density(
+  context: &CanvasRenderingContext2d,
+  size: (u32, u32),
+  cells: &CellSet,
+  offset: (f64, f64),
+  zoom: f64,
+  settings: &Settings,
+  theme: &Theme,
+) {
+  let grid_width = grid_width(zoom, settings);
+  let buckets = density_buckets(
+    cells,
+    (offset.0 + grid_width, offset.1 + grid_width),
+    pitch(zoom, settings),
+    size,
+  );
+  for (shade, pixels) in buckets.iter().enumerate() {
+    context.set_fill_style(&JsValue::from(to_hex(shade_color(shade, theme))));
+    for &(x, y) in pixels {
+      context.fill_rect(x as f64, y as f64, 1.0, 1.0);
+    }
+  }
+}
@@ -0,0 +1,208 @@
+use crate::color_utils::{mix, Rgb, BIRTH_COLOR, HEAT_COLD_COLOR, HEAT_HOT_COLOR};
+use crate::lexicon::Cell;
+use crate::life::CellSet;
+use crate::settings::Settings;
+use crate::theme::Theme;
+use std::collections::HashMap;
+
+/// Cells to draw grouped by color, in the order they must be drawn.
+pub type Layers = Vec<(Rgb, Vec<Cell>)>;
+
+/// How cells are colored, to show more or less of their history.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ColorMode {
+  /// Live cells over a grey trail of the previous generations.
+  Trail,
+  /// Cells colored by how many of the remembered generations they were alive
+  /// in: stators are hot, rotors are cold.
+  HeatMap,
+  /// Live cells born in this generation in one color, the ones which survived
+  /// from the previous generation in another.
+  BirthsSurvivors,
+}
+
+impl ColorMode {
+  pub const ALL: [ColorMode; 3] = [
+    ColorMode::Trail,
+    ColorMode::HeatMap,
+    ColorMode::BirthsSurvivors,
+  ];
+
+  pub fn label(self) -> &'static str {
+    match self {
+      ColorMode::Trail => "Trail",
+      ColorMode::HeatMap => "Heat map",
+      ColorMode::BirthsSurvivors => "Births vs. survivors",
+    }
+  }
+
+  /// Cells of the current generation and of the previous ones (the most
+  /// recent first) to draw, by color.
+  pub fn layers(
+    self,
+    cells: &CellSet,
+    previous_gens: &[CellSet],
+    settings: &Settings,
+    theme: &Theme,
+  ) -> Layers {
+    match self {
+      ColorMode::Trail => {
+        let trail = settings.trail(previous_gens);
+        let num_gens = trail.len();
+        let mut layers: Layers = trail
+          .iter()
+          .enumerate()
+          .rev()
+          .map(|(gen_index, gen)| {
+            let color = theme.trail_color(gen_index, num_gens);
+            (color, gen.iter().copied().collect())
+          })
+          .collect();
+        layers.push((theme.live_cell, cells.iter().copied().collect()));
+        layers
+      }
+      ColorMode::HeatMap => {
+        let num_gens = previous_gens.len() + 1;
+        let mut counts: HashMap<Cell, usize> = HashMap::new();
+        for gen in std::iter::once(cells).chain(previous_gens) {
+          for &cell in gen {
+            *counts.entry(cell).or_insert(0) += 1;
+          }
+        }
+        let mut layers: Layers = (1..=num_gens)
+          .map(|count| (heat_color(count, num_gens), vec![]))
+          .collect();
+        for (cell, count) in counts {
+          layers[count - 1].1.push(cell);
+        }
+        layers
+      }
+      ColorMode::BirthsSurvivors => {
+        let (survivors, births): (Vec<Cell>, Vec<Cell>) = match previous_gens.first() {
+          Some(previous) => cells
+            .iter()
+            .copied()
+            .partition(|cell| previous.contains(cell)),
+          // Without history, there is no telling which cells were born.
+          None => (cells.iter().copied().collect(), vec![]),
+        };
+        vec![(theme.live_cell, survivors), (BIRTH_COLOR, births)]
+      }
+    }
+  }
+
+  /// Colors used by the mode and what they mean.
+  pub fn legend(self, settings: &Settings, theme: &Theme) -> Vec<(Rgb, String)> {
+    match self {
+      ColorMode::Trail => {
+        let trail_length = settings.trail_length.min(settings.num_previous);
+        let mut legend = vec![(theme.live_cell, "Alive".to_string())];
+        if trail_length > 0 {
+          legend.push((
+            theme.trail_color(0, trail_length),
+            "Alive 1 generation ago".to_string(),
+          ));
+        }
+        if trail_length > 1 {
+          legend.push((
+            theme.trail_color(trail_length - 1, trail_length),
+            format!("Alive {} generations ago", trail_length),
+          ));
+        }
+        legend
+      }
+      ColorMode::HeatMap => {
+        let num_gens = settings.num_previous + 1;
+        vec![
+          (
+            heat_color(1, num_gens),
+            format!("Alive in 1 of the last {} generations", num_gens),
+          ),
+          (
+            heat_color(num_gens, num_gens),
+            format!("Alive in all the last {} generations", num_gens),
+          ),
+        ]
+      }
+      ColorMode::BirthsSurvivors => vec![
+        (BIRTH_COLOR, "Born in this generation".to_string()),
+        (theme.live_cell, "Survived the last generation".to_string()),
+      ],
+    }
+  }
+}
+
+/// Color of a cell alive in `count` of `num_gens` generations.
+fn heat_color(count: usize, num_gens: usize) -> Rgb {
+  if num_gens <= 1 {
+    return HEAT_HOT_COLOR;
+  }
+  mix(
+    HEAT_COLD_COLOR,
+    HEAT_HOT_COLOR,
+    (count - 1) as f64 / (num_gens - 1) as f64,
+  )
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::settings::default_settings;
+  use crate::theme::Preset;
+
+  fn cell_set(cells: &[(i32, i32)]) -> CellSet {
+    cells.iter().map(|&(x, y)| Cell { x, y }).collect()
+  }
+
+  #[test]
+  fn trail_draws_oldest_generations_first_and_live_cells_last() {
+    let cells = cell_set(&[(0, 0)]);
+    let previous_gens = [cell_set(&[(1, 0)]), cell_set(&[(2, 0)])];
+    let theme = Preset::Light.theme();
+    let layers = ColorMode::Trail.layers(&cells, &previous_gens, &default_settings(), &theme);
+    let firsts: Vec<Cell> = layers.iter().map(|(_, cells)| cells[0]).collect();
+    assert_eq!(
+      firsts,
+      vec![
+        Cell { x: 2, y: 0 },
+        Cell { x: 1, y: 0 },
+        Cell { x: 0, y: 0 }
+      ]
+    );
+    assert_eq!(layers[2].0, theme.live_cell);
+  }
+
+  #[test]
+  fn heat_map_groups_cells_by_how_often_they_were_alive() {
+    // A blinker: the center is always alive, the ends every other generation.
+    let vertical = cell_set(&[(1, 0), (1, 1), (1, 2)]);
+    let horizontal = cell_set(&[(0, 1), (1, 1), (2, 1)]);
+    let layers = ColorMode::HeatMap.layers(
+      &vertical,
+      &[horizontal, vertical.clone()],
+      &default_settings(),
+      &Preset::Light.theme(),
+    );
+    assert_eq!(layers.len(), 3);
+    assert_eq!(layers[0].0, HEAT_COLD_COLOR);
+    assert_eq!(layers[0].1.len(), 2);
+    assert_eq!(layers[1].1.len(), 2);
+    assert_eq!(layers[2], (HEAT_HOT_COLOR, vec![Cell { x: 1, y: 1 }]));
+  }
+
+  #[test]
+  fn births_and_survivors_compare_with_the_previous_generation() {
+    let cells = cell_set(&[(0, 0), (1, 0)]);
+    let theme = Preset::Dark.theme();
+    let previous_gens = [cell_set(&[(0, 0), (5, 5)])];
+    let layers =
+      ColorMode::BirthsSurvivors.layers(&cells, &previous_gens, &default_settings(), &theme);
+    assert_eq!(
+      layers,
+      vec![
+        (theme.live_cell, vec![Cell { x: 0, y: 0 }]),
+        (BIRTH_COLOR, vec![Cell { x: 1, y: 0 }]),
+      ]
+    );
+  }
+}
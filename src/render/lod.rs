@@ -0,0 +1,94 @@
+//! Level of detail used when cells are smaller than a pixel: instead of
+//! drawing every cell, cells are counted in pixel buckets and each pixel is
+//! shaded by the fraction of its cells that are alive.
+
+use crate::color_utils::{mix, Rgb};
+use crate::life::CellSet;
+use crate::theme::Theme;
+use std::collections::HashMap;
+
+/// Number of distinct shades pixels are drawn with.
+pub const SHADES: usize = 8;
+/// Intensity of the lightest shade, so that a lone cell still shows.
+const MIN_INTENSITY: f64 = 0.25;
+
+/// Whether cells are too small at this pitch to be drawn one by one.
+pub fn use_lod(pitch: f64) -> bool {
+  pitch < 1.0
+}
+
+/// Color of the pixels of shade `shade` (from 0, the lightest, to
+/// `SHADES - 1`).
+pub fn shade_color(shade: usize, theme: &Theme) -> Rgb {
+  let intensity = MIN_INTENSITY + (1.0 - MIN_INTENSITY) * shade as f64 / (SHADES - 1) as f64;
+  mix(theme.background, theme.live_cell, intensity)
+}
+
+/// Pixels of a `width` × `height` canvas containing live cells, grouped by
+/// shade. `origin` is the position of the cell `(0, 0)` in pixels, and
+/// `pitch` the size of a cell.
+pub fn density_buckets(
+  cells: &CellSet,
+  origin: (f64, f64),
+  pitch: f64,
+  (width, height): (u32, u32),
+) -> Vec<Vec<(u32, u32)>> {
+  let mut counts: HashMap<(u32, u32), u32> = HashMap::new();
+  for cell in cells {
+    let x = (origin.0 + pitch * cell.x as f64).floor();
+    let y = (origin.1 + pitch * cell.y as f64).floor();
+    if x >= 0.0 && y >= 0.0 && x < width as f64 && y < height as f64 {
+      *counts.entry((x as u32, y as u32)).or_insert(0) += 1;
+    }
+  }
+
+  // Number of cells fitting in a pixel.
+  let capacity = 1.0 / (pitch * pitch);
+  let mut buckets = vec![vec![]; SHADES];
+  for (pixel, count) in counts {
+    let occupancy = f64::min(1.0, count as f64 / capacity);
+    let shade = ((occupancy * SHADES as f64).ceil() as usize).clamp(1, SHADES) - 1;
+    buckets[shade].push(pixel);
+  }
+  buckets
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::lexicon::Cell;
+  use crate::theme::Preset;
+
+  #[test]
+  fn density_buckets_shade_pixels_by_occupancy() {
+    // Four cells per pixel.
+    let full: CellSet = [(0, 0), (1, 0), (0, 1), (1, 1)]
+      .iter()
+      .map(|&(x, y)| Cell { x, y })
+      .collect();
+    let lone: CellSet = [Cell { x: 4, y: 0 }].iter().copied().collect();
+    let cells: CellSet = full.union(&lone).copied().collect();
+
+    let buckets = density_buckets(&cells, (0.0, 0.0), 0.5, (10, 10));
+    assert_eq!(buckets[SHADES - 1], vec![(0, 0)]);
+    assert_eq!(buckets[SHADES / 4 - 1], vec![(2, 0)]);
+    assert_eq!(buckets.iter().map(Vec::len).sum::<usize>(), 2);
+  }
+
+  #[test]
+  fn density_buckets_skip_cells_outside_the_canvas() {
+    let cells: CellSet = [Cell { x: -1, y: 0 }, Cell { x: 100, y: 0 }]
+      .iter()
+      .copied()
+      .collect();
+    let buckets = density_buckets(&cells, (0.0, 0.0), 0.5, (10, 10));
+    assert!(buckets.iter().all(Vec::is_empty));
+  }
+
+  #[test]
+  fn shades_go_from_light_to_live_cell_color() {
+    let theme = Preset::Light.theme();
+    assert_ne!(shade_color(0, &theme), theme.background);
+    assert_eq!(shade_color(SHADES - 1, &theme), theme.live_cell);
+  }
+}
@@ -0,0 +1,37 @@
+//! Drawing of the board. `canvas` draws with one canvas call per rectangle,
+//! `pixels` draws in memory and copies the result to the canvas at once.
+//! `color_mode` decides the color of each cell.
+
+pub mod canvas;
+pub mod color_mode;
+pub mod lod;
+pub mod pixels;
+
+use crate::settings::Settings;
+use crate::viewport::pitch;
+use std::ops::Range;
+
+/// Columns and rows of cells visible in a canvas of the given size, with one
+/// more on each side.
+pub fn cell_range(
+  settings: &Settings,
+  offset: (f64, f64),
+  zoom: f64,
+  (width, height): (u32, u32),
+) -> (Range<i32>, Range<i32>) {
+  let pitch = pitch(zoom, settings);
+  let size_to_cells = |size: f64| (size / pitch).ceil() as i32;
+
+  let from_x = size_to_cells(-offset.0) - 1;
+  let to_x = from_x + size_to_cells(width as f64) + 1;
+  let from_y = size_to_cells(-offset.1) - 1;
+  let to_y = from_y + size_to_cells(height as f64) + 1;
+
+  (from_x..to_x, from_y..to_y)
+}
+
+/// Start and width of a grid line snapped to whole pixels, at least one pixel
+/// wide, so that grid lines are crisp instead of smeared over two pixels.
+pub fn snap_grid_line(start: f64, width: f64) -> (f64, f64) {
+  (start.round(), width.round().max(1.0))
+}
@@ -0,0 +1,159 @@
+use super::color_mode::Layers;
+use super::lod::{density_buckets, shade_color, use_lod};
+use super::{cell_range, snap_grid_line};
+use crate::export::RgbaImage;
+use crate::lexicon::Cell;
+use crate::life::CellSet;
+use crate::settings::Settings;
+use crate::theme::Theme;
+use crate::viewport::{grid_width, pitch};
+
+/// Draws the board into `image` like `canvas::draw_board` draws it on a canvas
+/// of the same size. The pixels are identical when every edge falls on a pixel
+/// boundary; elsewhere browsers may round the antialiasing differently.
+#[allow(clippy::too_many_arguments)]
+pub fn draw_board(
+  image: &mut RgbaImage,
+  cells: &CellSet,
+  layers: &Layers,
+  offset: (f64, f64),
+  zoom: f64,
+  settings: &Settings,
+  theme: &Theme,
+) {
+  image.clear(theme.background);
+  let size = (image.width, image.height);
+  let (pitch, grid_width) = (pitch(zoom, settings), grid_width(zoom, settings));
+
+  if use_lod(pitch) {
+    let origin = (offset.0 + grid_width, offset.1 + grid_width);
+    for (shade, pixels) in density_buckets(cells, origin, pitch, size)
+      .iter()
+      .enumerate()
+    {
+      for &(x, y) in pixels {
+        image.fill_rect(x, y, 1, 1, shade_color(shade, theme));
+      }
+    }
+    return;
+  }
+
+  let (range_x, range_y) = cell_range(settings, offset, zoom, size);
+  // Grid lines are at least a pixel wide, so a width of 0 hides the grid.
+  if zoom > settings.grid_min_zoom && settings.grid_width > 0.0 {
+    let color = theme.grid;
+    for i in range_x.clone() {
+      let (x, width) = snap_grid_line(offset.0 + i as f64 * pitch, grid_width);
+      let height = image.height as f64;
+      image.blend_rect(x, 0.0, width, height, color);
+    }
+    for j in range_y.clone() {
+      let (y, height) = snap_grid_line(offset.1 + j as f64 * pitch, grid_width);
+      let width = image.width as f64;
+      image.blend_rect(0.0, y, width, height, color);
+    }
+  }
+
+  for (color, cells) in layers {
+    let visible = cells.iter().filter(|Cell { x, y }| {
+      *x >= range_x.start && *x <= range_x.end && *y >= range_y.start && *y <= range_y.end
+    });
+    for cell in visible {
+      image.blend_rect(
+        offset.0 + grid_width + pitch * cell.x as f64,
+        offset.1 + grid_width + pitch * cell.y as f64,
+        zoom * settings.cell_size,
+        zoom * settings.cell_size,
+        *color,
+      );
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::render::color_mode::ColorMode;
+  use crate::settings::default_settings;
+  use crate::theme::Preset;
+
+  fn pixel(image: &RgbaImage, x: u32, y: u32) -> [u8; 3] {
+    let index = 4 * (y * image.width + x) as usize;
+    [
+      image.pixels[index],
+      image.pixels[index + 1],
+      image.pixels[index + 2],
+    ]
+  }
+
+  #[test]
+  fn draw_board_draws_grid_trail_and_cells() {
+    let settings = default_settings();
+    let cells: CellSet = [Cell { x: 1, y: 0 }].iter().copied().collect();
+    let trail: CellSet = [Cell { x: 0, y: 0 }].iter().copied().collect();
+    let theme = Preset::Light.theme();
+    let mut image = RgbaImage::new(100, 50, theme.background);
+    let layers = ColorMode::Trail.layers(&cells, &[trail], &settings, &theme);
+    draw_board(
+      &mut image,
+      &cells,
+      &layers,
+      (0.0, 0.0),
+      1.0,
+      &settings,
+      &theme,
+    );
+
+    // Grid lines half a pixel wide are widened to the whole first column and
+    // row of pixels.
+    assert_eq!(pixel(&image, 0, 30), theme.grid);
+    assert_eq!(pixel(&image, 50, 0), theme.grid);
+    assert_eq!(pixel(&image, 10, 10), theme.trail_color(0, 1));
+    assert_eq!(pixel(&image, 30, 10), theme.live_cell);
+    assert_eq!(pixel(&image, 60, 30), theme.background);
+  }
+
+  #[test]
+  fn draw_board_hides_the_grid_of_width_0() {
+    let settings = Settings {
+      grid_width: 0.0,
+      ..default_settings()
+    };
+    let theme = Preset::Light.theme();
+    let mut image = RgbaImage::new(100, 50, theme.grid);
+    let layers = ColorMode::Trail.layers(&CellSet::new(), &[], &settings, &theme);
+    draw_board(
+      &mut image,
+      &CellSet::new(),
+      &layers,
+      (0.0, 0.0),
+      1.0,
+      &settings,
+      &theme,
+    );
+    assert_eq!(pixel(&image, 0, 30), theme.background);
+    assert_eq!(pixel(&image, 50, 0), theme.background);
+  }
+
+  #[test]
+  fn draw_board_shades_pixels_when_zoomed_far_out() {
+    let settings = default_settings();
+    let cells: CellSet = (0..100)
+      .flat_map(|x| (0..100).map(move |y| Cell { x, y }))
+      .collect();
+    let theme = Preset::Dark.theme();
+    let mut image = RgbaImage::new(10, 10, theme.background);
+    let layers = ColorMode::Trail.layers(&cells, &[], &settings, &theme);
+    draw_board(
+      &mut image,
+      &cells,
+      &layers,
+      (0.0, 0.0),
+      0.001,
+      &settings,
+      &theme,
+    );
+    assert_eq!(pixel(&image, 0, 0), theme.live_cell);
+    assert_eq!(pixel(&image, 5, 5), theme.background);
+  }
+}
@@ -0,0 +1,449 @@
+//! A small SAT solver (CDCL: conflict-driven clause learning with watched
+//! literals, activity-based decisions and restarts), used to search for the
+//! predecessors of patterns.
+//!
+//! Clauses are all added before solving. Solving can be given a budget of
+//! conflicts and resumed afterwards, so that a long search can be paused to
+//! handle other messages, or stopped.
+
+use std::collections::BinaryHeap;
+use std::ops::Not;
+
+pub type Var = u32;
+
+/// A variable or its negation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Lit(u32);
+
+impl Lit {
+  pub fn positive(var: Var) -> Self {
+    Lit(var * 2)
+  }
+
+  pub fn negative(var: Var) -> Self {
+    Lit(var * 2 + 1)
+  }
+
+  pub fn var(self) -> Var {
+    self.0 / 2
+  }
+
+  pub fn is_negative(self) -> bool {
+    self.0 % 2 == 1
+  }
+
+  fn index(self) -> usize {
+    self.0 as usize
+  }
+}
+
+impl Not for Lit {
+  type Output = Lit;
+
+  fn not(self) -> Lit {
+    Lit(self.0 ^ 1)
+  }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Outcome {
+  /// Value of each variable in a solution.
+  Satisfiable(Vec<bool>),
+  Unsatisfiable,
+}
+
+/// Conflicts between restarts are this many times the Luby sequence.
+const RESTART_INTERVAL: u64 = 100;
+const ACTIVITY_DECAY: f64 = 0.95;
+
+/// Term `i` (from 1) of the Luby sequence: 1, 1, 2, 1, 1, 2, 4, 1…
+fn luby(mut i: u64) -> u64 {
+  loop {
+    let mut k = 1;
+    while (1 << k) - 1 < i {
+      k += 1;
+    }
+    if (1 << k) - 1 == i {
+      return 1 << (k - 1);
+    }
+    i -= (1 << (k - 1)) - 1;
+  }
+}
+
+#[derive(Default)]
+pub struct Solver {
+  clauses: Vec<Vec<Lit>>,
+  /// Clauses watching each literal, by literal: they are visited when it
+  /// becomes false.
+  watches: Vec<Vec<usize>>,
+  values: Vec<Option<bool>>,
+  levels: Vec<usize>,
+  /// Clause that implied the value of each variable, none for decisions.
+  reasons: Vec<Option<usize>>,
+  /// Value each variable had last, given again when deciding it.
+  polarities: Vec<bool>,
+  activities: Vec<f64>,
+  activity_increment: f64,
+  /// Unassigned variables by activity, with outdated entries skipped.
+  order: BinaryHeap<(u64, Var)>,
+  trail: Vec<Lit>,
+  /// Start of each decision level in the trail.
+  trail_levels: Vec<usize>,
+  /// Literals of the trail already propagated.
+  propagated: usize,
+  unsatisfiable: bool,
+  conflicts: u64,
+  restarts: u64,
+}
+
+impl Solver {
+  pub fn new() -> Self {
+    Self {
+      activity_increment: 1.0,
+      ..Self::default()
+    }
+  }
+
+  pub fn new_var(&mut self) -> Var {
+    let var = self.values.len() as Var;
+    self.values.push(None);
+    self.levels.push(0);
+    self.reasons.push(None);
+    self.polarities.push(false);
+    self.activities.push(0.0);
+    self.watches.push(vec![]);
+    self.watches.push(vec![]);
+    self.order.push((0, var));
+    var
+  }
+
+  pub fn num_vars(&self) -> usize {
+    self.values.len()
+  }
+
+  /// Conflicts met since the solver was created.
+  pub fn conflicts(&self) -> u64 {
+    self.conflicts
+  }
+
+  fn value(&self, lit: Lit) -> Option<bool> {
+    self.values[lit.var() as usize].map(|value| value != lit.is_negative())
+  }
+
+  fn level(&self) -> usize {
+    self.trail_levels.len()
+  }
+
+  fn assign(&mut self, lit: Lit, reason: Option<usize>) {
+    let var = lit.var() as usize;
+    self.values[var] = Some(!lit.is_negative());
+    self.levels[var] = self.level();
+    self.reasons[var] = reason;
+    self.trail.push(lit);
+  }
+
+  fn watch(&mut self, clause: Vec<Lit>) -> usize {
+    let index = self.clauses.len();
+    self.watches[clause[0].index()].push(index);
+    self.watches[clause[1].index()].push(index);
+    self.clauses.push(clause);
+    index
+  }
+
+  /// Adds a clause: at least one of the literals must be true.
+  pub fn add_clause(&mut self, lits: &[Lit]) {
+    let mut clause: Vec<Lit> = vec![];
+    for &lit in lits {
+      if clause.contains(&!lit) {
+        return;
+      }
+      if !clause.contains(&lit) {
+        clause.push(lit);
+      }
+    }
+    match clause.len() {
+      0 => self.unsatisfiable = true,
+      1 => match self.value(clause[0]) {
+        Some(true) => {}
+        Some(false) => self.unsatisfiable = true,
+        None => self.assign(clause[0], None),
+      },
+      _ => {
+        self.watch(clause);
+      }
+    }
+  }
+
+  /// Assigns the literals implied by the ones assigned, returning the clause
+  /// made false if any.
+  fn propagate(&mut self) -> Option<usize> {
+    while self.propagated < self.trail.len() {
+      let false_lit = !self.trail[self.propagated];
+      self.propagated += 1;
+      let watching = std::mem::take(&mut self.watches[false_lit.index()]);
+      let mut kept = Vec::with_capacity(watching.len());
+      let mut conflict = None;
+      for (i, &index) in watching.iter().enumerate() {
+        if conflict.is_some() {
+          kept.extend_from_slice(&watching[i..]);
+          break;
+        }
+        let clause = &mut self.clauses[index];
+        if clause[0] == false_lit {
+          clause.swap(0, 1);
+        }
+        let first = clause[0];
+        if self.values[first.var() as usize] == Some(!first.is_negative()) {
+          kept.push(index);
+          continue;
+        }
+        let values = &self.values;
+        let replacement = (2..clause.len()).find(|&k| {
+          let lit = clause[k];
+          values[lit.var() as usize] != Some(lit.is_negative())
+        });
+        match replacement {
+          Some(k) => {
+            clause.swap(1, k);
+            let lit = clause[1];
+            self.watches[lit.index()].push(index);
+          }
+          None => {
+            kept.push(index);
+            match self.value(first) {
+              Some(false) => conflict = Some(index),
+              _ => self.assign(first, Some(index)),
+            }
+          }
+        }
+      }
+      self.watches[false_lit.index()] = kept;
+      if conflict.is_some() {
+        return conflict;
+      }
+    }
+    None
+  }
+
+  fn bump(&mut self, var: Var) {
+    let activity = &mut self.activities[var as usize];
+    *activity += self.activity_increment;
+    if *activity > 1e100 {
+      for activity in &mut self.activities {
+        *activity *= 1e-100;
+      }
+      self.activity_increment *= 1e-100;
+      self.order = self
+        .activities
+        .iter()
+        .enumerate()
+        .filter(|&(var, _)| self.values[var].is_none())
+        .map(|(var, activity)| (activity.to_bits(), var as Var))
+        .collect();
+    } else if self.values[var as usize].is_none() {
+      self.order.push((activity.to_bits(), var));
+    }
+  }
+
+  /// Learns a clause from the conflict, implied by the decisions but not
+  /// depending on the last one, returning it and the level to go back to.
+  fn analyze(&mut self, conflict: usize) -> (Vec<Lit>, usize) {
+    let mut seen = vec![false; self.num_vars()];
+    let mut learnt = vec![Lit(0)];
+    let mut pending = 0;
+    let mut clause = conflict;
+    let mut index = self.trail.len();
+    let uip = loop {
+      let skip = usize::from(index < self.trail.len());
+      for k in skip..self.clauses[clause].len() {
+        let lit = self.clauses[clause][k];
+        let var = lit.var() as usize;
+        if !seen[var] && self.levels[var] > 0 {
+          seen[var] = true;
+          self.bump(lit.var());
+          if self.levels[var] == self.level() {
+            pending += 1;
+          } else {
+            learnt.push(lit);
+          }
+        }
+      }
+      loop {
+        index -= 1;
+        if seen[self.trail[index].var() as usize] {
+          break;
+        }
+      }
+      let lit = self.trail[index];
+      seen[lit.var() as usize] = false;
+      pending -= 1;
+      if pending == 0 {
+        break lit;
+      }
+      clause = self.reasons[lit.var() as usize].expect("implied literal");
+    };
+    learnt[0] = !uip;
+    let mut level = 0;
+    for k in 1..learnt.len() {
+      let var_level = self.levels[learnt[k].var() as usize];
+      if var_level > level {
+        level = var_level;
+        learnt.swap(1, k);
+      }
+    }
+    (learnt, level)
+  }
+
+  fn backtrack(&mut self, level: usize) {
+    if self.level() <= level {
+      return;
+    }
+    let start = self.trail_levels[level];
+    for lit in self.trail.drain(start..) {
+      let var = lit.var() as usize;
+      self.polarities[var] = !lit.is_negative();
+      self.values[var] = None;
+      self.reasons[var] = None;
+      self.order.push((self.activities[var].to_bits(), lit.var()));
+    }
+    self.trail_levels.truncate(level);
+    self.propagated = self.trail.len();
+  }
+
+  fn decide(&mut self) -> Option<Var> {
+    while let Some((activity, var)) = self.order.pop() {
+      let var_index = var as usize;
+      if self.values[var_index].is_none() && self.activities[var_index].to_bits() == activity {
+        return Some(var);
+      }
+    }
+    // Entries are only outdated, never missing, unless all are assigned.
+    (0..self.num_vars() as Var).find(|&var| self.values[var as usize].is_none())
+  }
+
+  /// Searches for a solution, giving up after `max_conflicts` conflicts: the
+  /// search can then be resumed by calling it again.
+  pub fn solve(&mut self, max_conflicts: u64) -> Option<Outcome> {
+    if self.unsatisfiable {
+      return Some(Outcome::Unsatisfiable);
+    }
+    let mut budget = max_conflicts;
+    let mut until_restart = luby(self.restarts + 1) * RESTART_INTERVAL;
+    loop {
+      if let Some(conflict) = self.propagate() {
+        self.conflicts += 1;
+        if self.level() == 0 {
+          self.unsatisfiable = true;
+          return Some(Outcome::Unsatisfiable);
+        }
+        let (learnt, level) = self.analyze(conflict);
+        self.backtrack(level);
+        if learnt.len() == 1 {
+          self.assign(learnt[0], None);
+        } else {
+          let first = learnt[0];
+          let index = self.watch(learnt);
+          self.assign(first, Some(index));
+        }
+        self.activity_increment /= ACTIVITY_DECAY;
+        budget = budget.saturating_sub(1);
+        until_restart = until_restart.saturating_sub(1);
+        if budget == 0 {
+          self.backtrack(0);
+          return None;
+        }
+        if until_restart == 0 {
+          self.restarts += 1;
+          until_restart = luby(self.restarts + 1) * RESTART_INTERVAL;
+          self.backtrack(0);
+        }
+        continue;
+      }
+      match self.decide() {
+        Some(var) => {
+          self.trail_levels.push(self.trail.len());
+          let lit = if self.polarities[var as usize] {
+            Lit::positive(var)
+          } else {
+            Lit::negative(var)
+          };
+          self.assign(lit, None);
+        }
+        None => {
+          let model = self.values.iter().map(|value| value.unwrap()).collect();
+          self.backtrack(0);
+          return Some(Outcome::Satisfiable(model));
+        }
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  /// Clauses of the pigeonhole problem: `pigeons` pigeons in `holes` holes,
+  /// with no two pigeons in the same hole.
+  fn pigeonhole(pigeons: u32, holes: u32) -> Solver {
+    let mut solver = Solver::new();
+    let vars: Vec<Vec<Var>> = (0..pigeons)
+      .map(|_| (0..holes).map(|_| solver.new_var()).collect())
+      .collect();
+    for pigeon in &vars {
+      let lits: Vec<Lit> = pigeon.iter().map(|&var| Lit::positive(var)).collect();
+      solver.add_clause(&lits);
+    }
+    for (a, first) in vars.iter().enumerate() {
+      for second in &vars[a + 1..] {
+        for (&x, &y) in first.iter().zip(second) {
+          solver.add_clause(&[Lit::negative(x), Lit::negative(y)]);
+        }
+      }
+    }
+    solver
+  }
+
+  #[test]
+  fn luby_sequence() {
+    let terms: Vec<u64> = (1..=15).map(luby).collect();
+    assert_eq!(terms, vec![1, 1, 2, 1, 1, 2, 4, 1, 1, 2, 1, 1, 2, 4, 8]);
+  }
+
+  #[test]
+  fn solutions_satisfy_the_clauses() {
+    let mut solver = pigeonhole(5, 5);
+    let clauses = solver.clauses.clone();
+    match solver.solve(u64::MAX) {
+      Some(Outcome::Satisfiable(model)) => {
+        for clause in clauses {
+          assert!(clause
+            .iter()
+            .any(|lit| model[lit.var() as usize] != lit.is_negative()));
+        }
+      }
+      outcome => panic!("expected a solution, got {:?}", outcome),
+    }
+  }
+
+  #[test]
+  fn unsatisfiable_problems_are_proved_so() {
+    assert_eq!(
+      pigeonhole(7, 6).solve(u64::MAX),
+      Some(Outcome::Unsatisfiable)
+    );
+    // Resumed after running out of conflicts.
+    let mut solver = pigeonhole(7, 6);
+    let outcome = loop {
+      if let Some(outcome) = solver.solve(10) {
+        break outcome;
+      }
+    };
+    assert_eq!(outcome, Outcome::Unsatisfiable);
+    let mut solver = Solver::new();
+    let var = solver.new_var();
+    solver.add_clause(&[Lit::positive(var)]);
+    solver.add_clause(&[Lit::negative(var)]);
+    assert_eq!(solver.solve(u64::MAX), Some(Outcome::Unsatisfiable));
+  }
+}
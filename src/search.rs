@@ -0,0 +1,234 @@
+use crate::lexicon::Term;
+use std::cmp::{Ordering, Reverse};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SortOrder {
+  Relevance,
+  Name,
+  Population,
+  Size,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Query {
+  pub text: String,
+  pub tags: Vec<String>,
+  pub sort: SortOrder,
+}
+
+impl Default for Query {
+  fn default() -> Self {
+    Self {
+      text: String::new(),
+      tags: vec![],
+      sort: SortOrder::Relevance,
+    }
+  }
+}
+
+/// Scores how well `pattern` matches `text`, if all its characters appear in
+/// `text` in the same order (ignoring case and whitespace). Consecutive
+/// characters, characters at the beginning of words, prefixes and exact
+/// matches score higher.
+pub fn fuzzy_score(pattern: &str, text: &str) -> Option<i32> {
+  let pattern: Vec<char> = pattern
+    .to_lowercase()
+    .chars()
+    .filter(|c| !c.is_whitespace())
+    .collect();
+  let text: Vec<char> = text.to_lowercase().chars().collect();
+  if pattern.is_empty() {
+    return Some(0);
+  }
+
+  let mut score = 0;
+  let mut matched = 0;
+  let mut previous_match = None;
+  for (i, &c) in text.iter().enumerate() {
+    if matched < pattern.len() && c == pattern[matched] {
+      score += 1;
+      if i > 0 && previous_match == Some(i - 1) {
+        score += 5;
+      }
+      if i == 0 || !text[i - 1].is_alphanumeric() {
+        score += 3;
+      }
+      previous_match = Some(i);
+      matched += 1;
+    }
+  }
+  if matched < pattern.len() {
+    return None;
+  }
+
+  if text == pattern {
+    score += 100;
+  } else if text.starts_with(&pattern) {
+    score += 50;
+  }
+  Some(score - (text.len() - pattern.len()) as i32 / 4)
+}
+
+fn matches_description(words: &[String], term: &Term) -> bool {
+  let description = term.description.to_lowercase();
+  !words.is_empty() && words.iter().all(|word| description.contains(word.as_str()))
+}
+
+fn has_tags(tags: &[String], term: &Term) -> bool {
+  tags.iter().all(|tag| term.tags.contains(tag))
+}
+
+/// Indices of the terms having a pattern that match `query`.
+pub fn search(terms: &[Term], query: &Query) -> Vec<usize> {
+  let words: Vec<String> = query
+    .text
+    .to_lowercase()
+    .split_whitespace()
+    .map(String::from)
+    .collect();
+  let mut results: Vec<(usize, i32)> = terms
+    .iter()
+    .enumerate()
+    .filter(|(_, term)| !term.cells.is_empty() && has_tags(&query.tags, term))
+    .filter_map(|(i, term)| {
+      // Matches on the name always rank above matches on the description.
+      fuzzy_score(&query.text, &term.name)
+        .map(|score| score + 1000)
+        .or_else(|| matches_description(&words, term).then_some(0))
+        .map(|score| (i, score))
+    })
+    .collect();
+
+  let by_name = |a: &usize, b: &usize| natural_cmp(&terms[*a].name, &terms[*b].name);
+  match query.sort {
+    SortOrder::Relevance => {
+      results.sort_by(|(a, score_a), (b, score_b)| score_b.cmp(score_a).then(by_name(a, b)))
+    }
+    SortOrder::Name => results.sort_by(|(a, _), (b, _)| by_name(a, b)),
+    SortOrder::Population => results.sort_by_key(|&(i, _)| terms[i].cells.len()),
+    SortOrder::Size => results.sort_by_key(|&(i, _)| terms[i].width * terms[i].height),
+  }
+  results.into_iter().map(|(i, _)| i).collect()
+}
+
+/// All the tags used in the lexicon, in natural order (so that "p2" comes
+/// before "p10").
+pub fn all_tags(terms: &[Term]) -> Vec<String> {
+  let mut tags: Vec<String> = terms.iter().flat_map(|term| term.tags.clone()).collect();
+  tags.sort_by(|a, b| natural_cmp(a, b));
+  tags.dedup();
+  tags
+}
+
+/// Compares strings case-insensitively, numbers being compared by value.
+pub fn natural_cmp(a: &str, b: &str) -> Ordering {
+  fn chunks(s: &str) -> Vec<(Reverse<bool>, u64, String)> {
+    let mut chunks = vec![];
+    let mut chars = s.chars().peekable();
+    while let Some(&c) = chars.peek() {
+      let is_digit = c.is_ascii_digit();
+      let mut chunk = String::new();
+      while let Some(&c) = chars.peek() {
+        if c.is_ascii_digit() != is_digit {
+          break;
+        }
+        chunk.push(c);
+        chars.next();
+      }
+      let value = if is_digit {
+        chunk.parse().unwrap_or(u64::MAX)
+      } else {
+        0
+      };
+      chunks.push((Reverse(is_digit), value, chunk.to_lowercase()));
+    }
+    chunks
+  }
+  chunks(a).cmp(&chunks(b)).then(a.cmp(b))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::lexicon::Cell;
+
+  fn term(name: &str, description: &str, tags: &[&str], cells: usize) -> Term {
+    Term {
+      name: name.to_string(),
+      description: description.to_string(),
+      tags: tags.iter().map(|tag| tag.to_string()).collect(),
+      cells: (0..cells as i32).map(|x| Cell { x, y: 0 }).collect(),
+      width: cells,
+      height: 1,
+    }
+  }
+
+  fn terms() -> Vec<Term> {
+    vec![
+      term(
+        "glider",
+        "The smallest {spaceship}.",
+        &["c/4 diagonally", "p4"],
+        5,
+      ),
+      term("blinker", "The smallest {oscillator}.", &["p2"], 3),
+      term("block", "The most common {still life}.", &["p1"], 4),
+      term("glider gun", "A {gun} that emits gliders.", &["p30"], 36),
+      term("spaceship", "A moving pattern.", &[], 0),
+    ]
+  }
+
+  #[test]
+  fn fuzzy_score_requires_characters_in_order() {
+    assert!(fuzzy_score("gld", "glider").is_some());
+    assert!(fuzzy_score("dlg", "glider").is_none());
+  }
+
+  #[test]
+  fn fuzzy_score_prefers_exact_and_prefix_matches() {
+    let exact = fuzzy_score("glider", "glider").unwrap();
+    let prefix = fuzzy_score("glider", "glider gun").unwrap();
+    let scattered = fuzzy_score("glider", "gosper glider gun").unwrap();
+    assert!(exact > prefix && prefix > scattered);
+  }
+
+  #[test]
+  fn search_ranks_name_matches_before_description_matches() {
+    let query = Query {
+      text: "glider".to_string(),
+      ..Query::default()
+    };
+    assert_eq!(search(&terms(), &query), vec![0, 3]);
+    let query = Query {
+      text: "smallest".to_string(),
+      ..Query::default()
+    };
+    assert_eq!(search(&terms(), &query), vec![1, 0]);
+  }
+
+  #[test]
+  fn search_filters_by_tags_and_skips_terms_without_pattern() {
+    let query = Query {
+      tags: vec!["p2".to_string()],
+      ..Query::default()
+    };
+    assert_eq!(search(&terms(), &query), vec![1]);
+    assert_eq!(search(&terms(), &Query::default()).len(), 4);
+  }
+
+  #[test]
+  fn search_sorts_by_population() {
+    let query = Query {
+      sort: SortOrder::Population,
+      ..Query::default()
+    };
+    assert_eq!(search(&terms(), &query), vec![1, 2, 0, 3]);
+  }
+
+  #[test]
+  fn natural_cmp_compares_numbers_by_value() {
+    let mut tags = vec!["p10", "p2", "c/4 diagonally", "p1"];
+    tags.sort_by(|a, b| natural_cmp(a, b));
+    assert_eq!(tags, vec!["c/4 diagonally", "p1", "p2", "p10"]);
+  }
+}
@@ -1,8 +1,37 @@
-#[derive(Clone, PartialEq)]
+use crate::keybindings::KeyBindings;
+use crate::life::CellSet;
+use crate::storage;
+use crate::theme::ThemeChoice;
+use serde::{Deserialize, Serialize};
+
+const STORAGE_KEY: &str = "lifeee.settings";
+
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Settings {
+  /// Size of a cell at zoom 1, in pixels.
   pub cell_size: f64,
+  /// Width of the grid lines, in pixels.
   pub grid_width: f64,
+  /// Number of previous generations remembered, e.g. for the heat map.
   pub num_previous: usize,
+  /// Number of previous generations drawn in the trail, up to
+  /// `num_previous`.
+  pub trail_length: usize,
+  /// The grid is only drawn above this zoom.
+  pub grid_min_zoom: f64,
+  pub max_zoom: f64,
+  /// Cells at most this many cells apart belong to the same object, when
+  /// picking or labelling objects.
+  pub object_distance: i32,
+  pub key_bindings: KeyBindings,
+  pub theme: ThemeChoice,
+}
+
+impl Default for Settings {
+  fn default() -> Self {
+    default_settings()
+  }
 }
 
 pub fn default_settings() -> Settings {
@@ -10,5 +39,135 @@ pub fn default_settings() -> Settings {
     cell_size: 20.0,
     grid_width: 0.5,
     num_previous: 10,
+    trail_length: 10,
+    grid_min_zoom: 0.3,
+    max_zoom: 5.0,
+    object_distance: 2,
+    key_bindings: KeyBindings::default(),
+    theme: ThemeChoice::System,
+  }
+}
+
+impl Settings {
+  /// The previous generations drawn in the trail, the most recent first.
+  pub fn trail<'a>(&self, previous_gens: &'a [CellSet]) -> &'a [CellSet] {
+    &previous_gens[..previous_gens.len().min(self.trail_length)]
+  }
+}
+
+/// A numeric setting, as edited in the settings dialog.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NumericSetting {
+  CellSize,
+  GridWidth,
+  NumPrevious,
+  TrailLength,
+  GridMinZoom,
+  MaxZoom,
+  ObjectDistance,
+}
+
+impl NumericSetting {
+  pub const ALL: [NumericSetting; 7] = [
+    NumericSetting::CellSize,
+    NumericSetting::GridWidth,
+    NumericSetting::NumPrevious,
+    NumericSetting::TrailLength,
+    NumericSetting::GridMinZoom,
+    NumericSetting::MaxZoom,
+    NumericSetting::ObjectDistance,
+  ];
+
+  pub fn label(self) -> &'static str {
+    match self {
+      NumericSetting::CellSize => "Cell size (pixels)",
+      NumericSetting::GridWidth => "Grid line width (pixels)",
+      NumericSetting::NumPrevious => "Generations remembered",
+      NumericSetting::TrailLength => "Trail length (generations)",
+      NumericSetting::GridMinZoom => "Hide the grid below zoom",
+      NumericSetting::MaxZoom => "Maximum zoom",
+      NumericSetting::ObjectDistance => "Object distance (cells)",
+    }
+  }
+
+  /// Minimum, maximum and step of the values.
+  pub fn range(self) -> (f64, f64, f64) {
+    match self {
+      NumericSetting::CellSize => (2.0, 100.0, 1.0),
+      NumericSetting::GridWidth => (0.0, 5.0, 0.5),
+      NumericSetting::NumPrevious | NumericSetting::TrailLength => (0.0, 100.0, 1.0),
+      NumericSetting::GridMinZoom => (0.05, 5.0, 0.05),
+      NumericSetting::MaxZoom => (1.0, 50.0, 1.0),
+      NumericSetting::ObjectDistance => (1.0, 10.0, 1.0),
+    }
+  }
+
+  pub fn get(self, settings: &Settings) -> f64 {
+    match self {
+      NumericSetting::CellSize => settings.cell_size,
+      NumericSetting::GridWidth => settings.grid_width,
+      NumericSetting::NumPrevious => settings.num_previous as f64,
+      NumericSetting::TrailLength => settings.trail_length as f64,
+      NumericSetting::GridMinZoom => settings.grid_min_zoom,
+      NumericSetting::MaxZoom => settings.max_zoom,
+      NumericSetting::ObjectDistance => settings.object_distance as f64,
+    }
+  }
+
+  /// Settings with this one changed to `value`, brought within its range. The
+  /// trail is kept no longer than the generations remembered.
+  pub fn set(self, settings: &Settings, value: f64) -> Settings {
+    let (min, max, _) = self.range();
+    let value = value.clamp(min, max);
+    let mut settings = settings.clone();
+    match self {
+      NumericSetting::CellSize => settings.cell_size = value,
+      NumericSetting::GridWidth => settings.grid_width = value,
+      NumericSetting::NumPrevious => {
+        settings.num_previous = value.round() as usize;
+        settings.trail_length = settings.trail_length.min(settings.num_previous);
+      }
+      NumericSetting::TrailLength => {
+        settings.trail_length = value.round() as usize;
+        settings.num_previous = settings.num_previous.max(settings.trail_length);
+      }
+      NumericSetting::GridMinZoom => settings.grid_min_zoom = value,
+      NumericSetting::MaxZoom => settings.max_zoom = value,
+      NumericSetting::ObjectDistance => settings.object_distance = value.round() as i32,
+    }
+    settings
+  }
+}
+
+/// Settings saved in the browser, or the default ones. Settings missing from
+/// what was saved (e.g. added in a later version) get their default value.
+pub fn load_settings() -> Settings {
+  let mut settings: Settings = storage::load(STORAGE_KEY).unwrap_or_else(default_settings);
+  settings.key_bindings.bind_defaults_of_unbound();
+  settings
+}
+
+pub fn save_settings(settings: &Settings) {
+  storage::save(STORAGE_KEY, settings);
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn numeric_settings_stay_within_their_range() {
+    let settings = NumericSetting::CellSize.set(&default_settings(), 1000.0);
+    assert_eq!(settings.cell_size, 100.0);
+    let settings = NumericSetting::GridMinZoom.set(&settings, 0.0);
+    assert_eq!(settings.grid_min_zoom, 0.05);
+  }
+
+  #[test]
+  fn trail_is_no_longer_than_the_generations_remembered() {
+    let settings = NumericSetting::NumPrevious.set(&default_settings(), 4.0);
+    assert_eq!((settings.num_previous, settings.trail_length), (4, 4));
+    let settings = NumericSetting::TrailLength.set(&settings, 20.0);
+    assert_eq!((settings.num_previous, settings.trail_length), (20, 20));
   }
 }
@@ -0,0 +1,247 @@
+//! Random soups, filling a square at a given density from a seed, with the
+//! symmetries of apgsearch.
+
+use crate::hash::fnv1a;
+use crate::lexicon::Cell;
+use crate::life::CellSet;
+use serde::{Deserialize, Serialize};
+
+/// Random numbers reproducible from a text seed (SplitMix64).
+pub struct Random {
+  state: u64,
+}
+
+impl Random {
+  pub fn new(seed: &str) -> Self {
+    Self {
+      state: fnv1a(seed.bytes()),
+    }
+  }
+
+  pub fn next_u64(&mut self) -> u64 {
+    self.state = self.state.wrapping_add(0x9e3779b97f4a7c15);
+    let mut z = self.state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+    z ^ (z >> 31)
+  }
+
+  /// A number in `[0, 1)`.
+  pub fn next_f64(&mut self) -> f64 {
+    (self.next_u64() >> 11) as f64 / (1_u64 << 53) as f64
+  }
+}
+
+/// Symmetries of the soups, named like in apgsearch and Catagolue. The digit
+/// after the underscore tells where the center is: 1 on a cell, 2 on the edge
+/// between two cells, 4 on the corner between four cells.
+#[allow(non_camel_case_types)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Symmetry {
+  C1,
+  C2_1,
+  C2_2,
+  C2_4,
+  C4_1,
+  C4_4,
+  D2Plus1,
+  D2Plus2,
+  D2X,
+  D4Plus1,
+  D4Plus2,
+  D4Plus4,
+  D4X1,
+  D4X4,
+  D8_1,
+  D8_4,
+}
+
+/// Transform of a cell: whether x and y are swapped, then whether x and y are
+/// mirrored.
+type Transform = (bool, bool, bool);
+
+const IDENTITY: Transform = (false, false, false);
+const ROTATE_180: Transform = (false, true, true);
+const ROTATE_90: Transform = (true, true, false);
+const ROTATE_270: Transform = (true, false, true);
+const MIRROR_X: Transform = (false, true, false);
+const MIRROR_Y: Transform = (false, false, true);
+const DIAGONAL: Transform = (true, false, false);
+const ANTI_DIAGONAL: Transform = (true, true, true);
+
+impl Symmetry {
+  pub const ALL: [Symmetry; 16] = [
+    Symmetry::C1,
+    Symmetry::C2_1,
+    Symmetry::C2_2,
+    Symmetry::C2_4,
+    Symmetry::C4_1,
+    Symmetry::C4_4,
+    Symmetry::D2Plus1,
+    Symmetry::D2Plus2,
+    Symmetry::D2X,
+    Symmetry::D4Plus1,
+    Symmetry::D4Plus2,
+    Symmetry::D4Plus4,
+    Symmetry::D4X1,
+    Symmetry::D4X4,
+    Symmetry::D8_1,
+    Symmetry::D8_4,
+  ];
+
+  pub fn label(self) -> &'static str {
+    match self {
+      Symmetry::C1 => "C1",
+      Symmetry::C2_1 => "C2_1",
+      Symmetry::C2_2 => "C2_2",
+      Symmetry::C2_4 => "C2_4",
+      Symmetry::C4_1 => "C4_1",
+      Symmetry::C4_4 => "C4_4",
+      Symmetry::D2Plus1 => "D2_+1",
+      Symmetry::D2Plus2 => "D2_+2",
+      Symmetry::D2X => "D2_x",
+      Symmetry::D4Plus1 => "D4_+1",
+      Symmetry::D4Plus2 => "D4_+2",
+      Symmetry::D4Plus4 => "D4_+4",
+      Symmetry::D4X1 => "D4_x1",
+      Symmetry::D4X4 => "D4_x4",
+      Symmetry::D8_1 => "D8_1",
+      Symmetry::D8_4 => "D8_4",
+    }
+  }
+
+  /// Transforms of the symmetry, and how much mirroring shifts x and y: 0 to
+  /// mirror around a cell, 1 around the edge between two cells.
+  fn transforms(self) -> (&'static [Transform], (i32, i32)) {
+    const C2: &[Transform] = &[IDENTITY, ROTATE_180];
+    const C4: &[Transform] = &[IDENTITY, ROTATE_90, ROTATE_180, ROTATE_270];
+    const D2_PLUS: &[Transform] = &[IDENTITY, MIRROR_Y];
+    const D2_X: &[Transform] = &[IDENTITY, DIAGONAL];
+    const D4_PLUS: &[Transform] = &[IDENTITY, MIRROR_X, MIRROR_Y, ROTATE_180];
+    const D4_X: &[Transform] = &[IDENTITY, DIAGONAL, ANTI_DIAGONAL, ROTATE_180];
+    const D8: &[Transform] = &[
+      IDENTITY,
+      ROTATE_90,
+      ROTATE_180,
+      ROTATE_270,
+      MIRROR_X,
+      MIRROR_Y,
+      DIAGONAL,
+      ANTI_DIAGONAL,
+    ];
+    match self {
+      Symmetry::C1 => (&[IDENTITY], (0, 0)),
+      Symmetry::C2_1 => (C2, (0, 0)),
+      Symmetry::C2_2 => (C2, (1, 0)),
+      Symmetry::C2_4 => (C2, (1, 1)),
+      Symmetry::C4_1 => (C4, (0, 0)),
+      Symmetry::C4_4 => (C4, (1, 1)),
+      Symmetry::D2Plus1 => (D2_PLUS, (0, 0)),
+      Symmetry::D2Plus2 => (D2_PLUS, (0, 1)),
+      Symmetry::D2X => (D2_X, (0, 0)),
+      Symmetry::D4Plus1 => (D4_PLUS, (0, 0)),
+      Symmetry::D4Plus2 => (D4_PLUS, (1, 0)),
+      Symmetry::D4Plus4 => (D4_PLUS, (1, 1)),
+      Symmetry::D4X1 => (D4_X, (0, 0)),
+      Symmetry::D4X4 => (D4_X, (1, 1)),
+      Symmetry::D8_1 => (D8, (0, 0)),
+      Symmetry::D8_4 => (D8, (1, 1)),
+    }
+  }
+}
+
+fn transform(cell: Cell, (swap, mirror_x, mirror_y): Transform, (dx, dy): (i32, i32)) -> Cell {
+  let (x, y) = if swap {
+    (cell.y, cell.x)
+  } else {
+    (cell.x, cell.y)
+  };
+  Cell {
+    x: if mirror_x { -x - dx } else { x },
+    y: if mirror_y { -y - dy } else { y },
+  }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SoupOptions {
+  /// Width and height of the random square, before applying the symmetry.
+  pub size: u32,
+  /// Probability of each cell of the square to be alive.
+  pub density: f64,
+  pub symmetry: Symmetry,
+}
+
+impl Default for SoupOptions {
+  fn default() -> Self {
+    Self {
+      size: 16,
+      density: 0.5,
+      symmetry: Symmetry::C1,
+    }
+  }
+}
+
+/// Soup made from the seed: the same seed and options always give the same
+/// soup.
+pub fn soup(seed: &str, options: &SoupOptions) -> CellSet {
+  let mut random = Random::new(seed);
+  let (transforms, shift) = options.symmetry.transforms();
+  let size = options.size as i32;
+  let mut cells = CellSet::new();
+  for y in 0..size {
+    for x in 0..size {
+      if random.next_f64() < options.density {
+        for &t in transforms {
+          cells.insert(transform(Cell { x, y }, t, shift));
+        }
+      }
+    }
+  }
+  cells
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn seed_reproduces_the_soup() {
+    let options = SoupOptions::default();
+    assert_eq!(soup("abc", &options), soup("abc", &options));
+    assert_ne!(soup("abc", &options), soup("abd", &options));
+    let population = soup("abc", &options).len();
+    assert!((80..=176).contains(&population), "{}", population);
+    let empty = SoupOptions {
+      density: 0.0,
+      ..options
+    };
+    assert!(soup("abc", &empty).is_empty());
+  }
+
+  #[test]
+  fn soups_have_their_symmetry() {
+    for symmetry in Symmetry::ALL {
+      let options = SoupOptions {
+        symmetry,
+        ..SoupOptions::default()
+      };
+      let cells = soup("symmetric", &options);
+      let (transforms, shift) = symmetry.transforms();
+      for &t in transforms {
+        let transformed: CellSet = cells
+          .iter()
+          .map(|&cell| transform(cell, t, shift))
+          .collect();
+        assert_eq!(transformed, cells, "{}", symmetry.label());
+      }
+    }
+    // The center of C2_4 is on the corner between cells, so that the soup is
+    // twice as wide.
+    let options = SoupOptions {
+      density: 1.0,
+      symmetry: Symmetry::C2_4,
+      ..SoupOptions::default()
+    };
+    assert_eq!(soup("full", &options).len(), 2 * 16 * 16);
+  }
+}
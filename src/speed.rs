@@ -0,0 +1,158 @@
+//! How fast the simulation runs, and measuring how fast it actually runs.
+
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+/// Usual refresh rate of screens.
+pub const FRAMES_PER_SECOND: f64 = 60.0;
+/// Rates are measured over this duration, in milliseconds.
+const METER_WINDOW: f64 = 1000.0;
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Speed {
+  /// `2^exponent` generations per frame, like the step exponent of Golly: 0
+  /// is a generation per frame, 3 is 8, and -2 is one every 4 frames.
+  StepExponent(i32),
+  /// Exact number of generations per second.
+  Target(f64),
+}
+
+impl Speed {
+  pub const MIN_EXPONENT: i32 = -6;
+  pub const MAX_EXPONENT: i32 = 10;
+  pub const MAX_TARGET: f64 = 100_000.0;
+
+  /// Generations per frame with a step exponent, possibly less than one.
+  pub fn gens_per_frame(self) -> Option<f64> {
+    match self {
+      Speed::StepExponent(exponent) => Some(2_f64.powi(exponent)),
+      Speed::Target(_) => None,
+    }
+  }
+
+  /// Generations per second, assuming `FRAMES_PER_SECOND` with a step
+  /// exponent.
+  pub fn gens_per_second(self) -> f64 {
+    match self {
+      Speed::StepExponent(exponent) => FRAMES_PER_SECOND * 2_f64.powi(exponent),
+      Speed::Target(gens_per_second) => gens_per_second,
+    }
+  }
+
+  /// The step exponent closest to this speed.
+  pub fn as_step_exponent(self) -> Speed {
+    match self {
+      Speed::StepExponent(_) => self,
+      Speed::Target(gens_per_second) => Speed::StepExponent(
+        ((gens_per_second / FRAMES_PER_SECOND).log2().round() as i32)
+          .clamp(Self::MIN_EXPONENT, Self::MAX_EXPONENT),
+      ),
+    }
+  }
+
+  pub fn label(self) -> String {
+    match self {
+      Speed::StepExponent(exponent) if exponent < 0 => {
+        format!("1 gen every {} frames", 1 << -exponent)
+      }
+      Speed::StepExponent(exponent) => format!("{} gen/frame", 1 << exponent),
+      Speed::Target(gens_per_second) => format!("{} per second", gens_per_second),
+    }
+  }
+
+  /// Twice as fast, up to the maximum.
+  pub fn faster(self) -> Speed {
+    match self {
+      Speed::StepExponent(exponent) => Speed::StepExponent((exponent + 1).min(Self::MAX_EXPONENT)),
+      Speed::Target(gens_per_second) => {
+        Speed::Target((gens_per_second * 2.0).min(Self::MAX_TARGET))
+      }
+    }
+  }
+
+  /// Twice as slow, down to the minimum.
+  pub fn slower(self) -> Speed {
+    match self {
+      Speed::StepExponent(exponent) => Speed::StepExponent((exponent - 1).max(Self::MIN_EXPONENT)),
+      Speed::Target(gens_per_second) => Speed::Target((gens_per_second / 2.0).max(1.0)),
+    }
+  }
+}
+
+impl Default for Speed {
+  fn default() -> Self {
+    Speed::StepExponent(-4)
+  }
+}
+
+/// Measures how often something happens, e.g. frames drawn or generations
+/// computed, over the last second.
+#[derive(Default)]
+pub struct RateMeter {
+  /// Times (in milliseconds) and total counts recorded, oldest first.
+  samples: VecDeque<(f64, f64)>,
+}
+
+impl RateMeter {
+  /// Records that `total` things happened so far, at `time`.
+  pub fn record(&mut self, time: f64, total: f64) {
+    self.samples.push_back((time, total));
+    while let Some(&(oldest, _)) = self.samples.front() {
+      if time - oldest <= METER_WINDOW {
+        break;
+      }
+      self.samples.pop_front();
+    }
+  }
+
+  /// Things happening per second, if measured long enough.
+  pub fn rate(&self) -> Option<f64> {
+    let (first_time, first_total) = *self.samples.front()?;
+    let (last_time, last_total) = *self.samples.back()?;
+    if last_time <= first_time {
+      return None;
+    }
+    Some((last_total - first_total) / (last_time - first_time) * 1000.0)
+  }
+
+  pub fn clear(&mut self) {
+    self.samples.clear();
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn step_exponent_doubles_the_generations_per_frame() {
+    assert_eq!(Speed::StepExponent(0).gens_per_frame(), Some(1.0));
+    assert_eq!(Speed::StepExponent(3).gens_per_frame(), Some(8.0));
+    assert_eq!(Speed::StepExponent(-2).gens_per_frame(), Some(0.25));
+    assert_eq!(Speed::Target(10.0).gens_per_frame(), None);
+    assert_eq!(
+      Speed::StepExponent(Speed::MAX_EXPONENT).faster(),
+      Speed::StepExponent(Speed::MAX_EXPONENT)
+    );
+    assert_eq!(Speed::Target(10.0).slower(), Speed::Target(5.0));
+    assert_eq!(Speed::StepExponent(3).label(), "8 gen/frame");
+    assert_eq!(Speed::StepExponent(-2).label(), "1 gen every 4 frames");
+    assert_eq!(
+      Speed::Target(500.0).as_step_exponent(),
+      Speed::StepExponent(3)
+    );
+  }
+
+  #[test]
+  fn rate_is_measured_over_the_last_second() {
+    let mut meter = RateMeter::default();
+    assert_eq!(meter.rate(), None);
+    meter.record(0.0, 0.0);
+    assert_eq!(meter.rate(), None);
+    meter.record(500.0, 100.0);
+    assert_eq!(meter.rate(), Some(200.0));
+    // The first sample is forgotten, and only the last second counts.
+    meter.record(1500.0, 150.0);
+    assert_eq!(meter.rate(), Some(50.0));
+  }
+}
@@ -0,0 +1,307 @@
+//! Conditions pausing the simulation on their own, e.g. when exploring soups.
+
+use crate::lexicon::Cell;
+use crate::life::CellSet;
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::VecDeque;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct StopConditions {
+  /// Stops when this generation is reached.
+  pub at_generation: Option<u32>,
+  /// Stops when the population dies out.
+  pub extinct: bool,
+  /// Stops when the population stayed the same for this many generations.
+  pub stable_for: Option<u32>,
+  /// Stops when the board returns to a state it was already in.
+  pub repeated: bool,
+  /// Stops when a cell comes alive outside of this region, top-left and
+  /// bottom-right cells.
+  pub region: Option<(Cell, Cell)>,
+  /// Stops when a cell of this area is born or dies.
+  pub watched_area: Option<(Cell, Cell)>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum StopReason {
+  Generation(u32),
+  Extinct,
+  Stable(u32),
+  /// Back to the state of an earlier generation.
+  Repeated {
+    since: u32,
+    period: u32,
+  },
+  LeftRegion,
+  WatchedAreaChanged,
+}
+
+impl fmt::Display for StopReason {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      StopReason::Generation(generation) => write!(f, "Reached generation {}", generation),
+      StopReason::Extinct => write!(f, "The population died out"),
+      StopReason::Stable(generations) => write!(
+        f,
+        "The population stayed the same for {} generations",
+        generations
+      ),
+      StopReason::Repeated { since, period } => write!(
+        f,
+        "Back to the state of generation {} (period {})",
+        since, period
+      ),
+      StopReason::LeftRegion => write!(f, "The pattern left the region"),
+      StopReason::WatchedAreaChanged => write!(f, "A cell of the watched area changed"),
+    }
+  }
+}
+
+/// Generations remembered to notice a repeated state: states repeating with a
+/// longer period aren't noticed.
+const REPEAT_WINDOW: usize = 256;
+
+fn is_inside((min, max): (Cell, Cell), cell: Cell) -> bool {
+  (min.x..=max.x).contains(&cell.x) && (min.y..=max.y).contains(&cell.y)
+}
+
+/// A state of the board seen at a generation: its live cells, sorted so that
+/// states can be compared and hashed whatever order the sets list them in.
+struct State {
+  tick: u32,
+  hash: u64,
+  cells: Vec<(i32, i32)>,
+}
+
+impl State {
+  fn new(tick: u32, cells: &CellSet) -> Self {
+    let mut sorted: Vec<(i32, i32)> = cells.iter().map(|cell| (cell.x, cell.y)).collect();
+    sorted.sort_unstable();
+    let mut hasher = DefaultHasher::new();
+    sorted.hash(&mut hasher);
+    Self {
+      tick,
+      hash: hasher.finish(),
+      cells: sorted,
+    }
+  }
+}
+
+/// Checks the stop conditions at each generation, remembering what they need
+/// of the previous ones. Conditions are only met when something changes, so
+/// that the simulation can go on after stopping, e.g. a pattern that left the
+/// region doesn't stop it again at the next generation.
+#[derive(Default)]
+pub struct StopChecker {
+  conditions: StopConditions,
+  population: usize,
+  /// Generations the population stayed the same for.
+  stable_for: u32,
+  /// The last `REPEAT_WINDOW` states seen, oldest first.
+  seen: VecDeque<State>,
+  /// Whether cells were alive outside of the region.
+  outside: bool,
+  /// Live cells of the watched area.
+  watched_cells: CellSet,
+}
+
+impl StopChecker {
+  pub fn new(conditions: StopConditions) -> Self {
+    Self {
+      conditions,
+      ..Self::default()
+    }
+  }
+
+  /// Starts checking from these cells, forgetting the previous generations.
+  pub fn reset(&mut self, tick: u32, cells: &CellSet) {
+    self.population = cells.len();
+    self.stable_for = 0;
+    self.seen.clear();
+    if self.conditions.repeated {
+      self.seen.push_back(State::new(tick, cells));
+    }
+    self.outside = self.is_outside(cells);
+    self.watched_cells = self.watched_cells(cells);
+  }
+
+  fn is_outside(&self, cells: &CellSet) -> bool {
+    self
+      .conditions
+      .region
+      .is_some_and(|region| cells.iter().any(|&cell| !is_inside(region, cell)))
+  }
+
+  fn watched_cells(&self, cells: &CellSet) -> CellSet {
+    match self.conditions.watched_area {
+      Some(area) => cells
+        .iter()
+        .copied()
+        .filter(|&cell| is_inside(area, cell))
+        .collect(),
+      None => CellSet::new(),
+    }
+  }
+
+  /// The first condition met by the cells of generation `tick`, if any.
+  pub fn check(&mut self, tick: u32, cells: &CellSet) -> Option<StopReason> {
+    let mut reasons = vec![];
+
+    if self.conditions.at_generation == Some(tick) {
+      reasons.push(StopReason::Generation(tick));
+    }
+
+    let population = cells.len();
+    if self.conditions.extinct && population == 0 && self.population > 0 {
+      reasons.push(StopReason::Extinct);
+    }
+    self.stable_for = if population == self.population {
+      self.stable_for + 1
+    } else {
+      0
+    };
+    self.population = population;
+    if let Some(generations) = self.conditions.stable_for {
+      if self.stable_for == generations {
+        reasons.push(StopReason::Stable(generations));
+      }
+    }
+
+    if self.conditions.repeated {
+      let state = State::new(tick, cells);
+      // Different states can have the same hash, so the cells are compared.
+      let since = self
+        .seen
+        .iter()
+        .rev()
+        .find(|seen| seen.hash == state.hash && seen.cells == state.cells)
+        .map(|seen| seen.tick);
+      if let Some(since) = since {
+        reasons.push(StopReason::Repeated {
+          since,
+          period: tick - since,
+        });
+      }
+      if self.seen.len() == REPEAT_WINDOW {
+        self.seen.pop_front();
+      }
+      self.seen.push_back(state);
+    }
+
+    let outside = self.is_outside(cells);
+    if outside && !self.outside {
+      reasons.push(StopReason::LeftRegion);
+    }
+    self.outside = outside;
+
+    let watched_cells = self.watched_cells(cells);
+    if watched_cells != self.watched_cells {
+      reasons.push(StopReason::WatchedAreaChanged);
+    }
+    self.watched_cells = watched_cells;
+
+    reasons.into_iter().next()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::life::{cells, tick};
+
+  /// Runs generations until a condition is met, up to `max` generations.
+  fn run(conditions: StopConditions, mut cells: CellSet, max: u32) -> Option<(u32, StopReason)> {
+    let mut checker = StopChecker::new(conditions);
+    checker.reset(0, &cells);
+    (1..=max).find_map(|generation| {
+      cells = tick(&cells);
+      checker
+        .check(generation, &cells)
+        .map(|reason| (generation, reason))
+    })
+  }
+
+  #[test]
+  fn blinker_repeats_with_period_2() {
+    let blinker = cells(&[(0, 1), (1, 1), (2, 1)]);
+    let conditions = StopConditions {
+      repeated: true,
+      ..StopConditions::default()
+    };
+    assert_eq!(
+      run(conditions, blinker.clone(), 10),
+      Some((
+        2,
+        StopReason::Repeated {
+          since: 0,
+          period: 2
+        }
+      ))
+    );
+    let conditions = StopConditions {
+      stable_for: Some(3),
+      ..StopConditions::default()
+    };
+    assert_eq!(
+      run(conditions, blinker, 10),
+      Some((3, StopReason::Stable(3)))
+    );
+  }
+
+  #[test]
+  fn only_the_last_generations_are_remembered() {
+    let conditions = StopConditions {
+      repeated: true,
+      ..StopConditions::default()
+    };
+    let mut checker = StopChecker::new(conditions);
+    let mut glider = cells(&[(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)]);
+    checker.reset(0, &glider);
+    for generation in 1..=2 * REPEAT_WINDOW as u32 {
+      glider = tick(&glider);
+      assert_eq!(checker.check(generation, &glider), None);
+    }
+    assert_eq!(checker.seen.len(), REPEAT_WINDOW);
+  }
+
+  #[test]
+  fn lone_cell_dies_out() {
+    let conditions = StopConditions {
+      extinct: true,
+      at_generation: Some(5),
+      ..StopConditions::default()
+    };
+    assert_eq!(
+      run(conditions.clone(), cells(&[(0, 0)]), 10),
+      Some((1, StopReason::Extinct))
+    );
+    assert_eq!(
+      run(conditions, cells(&[(0, 0), (0, 1), (1, 0), (1, 1)]), 10),
+      Some((5, StopReason::Generation(5)))
+    );
+  }
+
+  #[test]
+  fn glider_leaves_the_region_and_crosses_the_watched_area() {
+    let glider = cells(&[(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)]);
+    let conditions = StopConditions {
+      region: Some((Cell { x: -1, y: -1 }, Cell { x: 3, y: 3 })),
+      ..StopConditions::default()
+    };
+    assert_eq!(
+      run(conditions, glider.clone(), 20),
+      Some((5, StopReason::LeftRegion))
+    );
+    let conditions = StopConditions {
+      watched_area: Some((Cell { x: 10, y: 10 }, Cell { x: 10, y: 10 })),
+      ..StopConditions::default()
+    };
+    assert!(matches!(
+      run(conditions, glider, 60),
+      Some((_, StopReason::WatchedAreaChanged))
+    ));
+  }
+}
@@ -0,0 +1,14 @@
+use gloo::storage::{LocalStorage, Storage};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+/// Reads a value saved in the browser, if any and still readable.
+pub fn load<T: DeserializeOwned>(key: &str) -> Option<T> {
+  LocalStorage::get(key).ok()
+}
+
+pub fn save<T: Serialize>(key: &str, value: &T) {
+  // Storage may be full or disabled (e.g. in private browsing), in which case
+  // there is nothing better to do than keeping the value for this session.
+  LocalStorage::set(key, value).ok();
+}
@@ -0,0 +1,198 @@
+//! Colors of the board, chosen among presets or edited by the user.
+
+use crate::color_utils::{grey_rgb, mix, Rgb, BACKGROUND_COLOR, GRID_GREY, LIVE_CELL_COLOR};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Theme {
+  pub background: Rgb,
+  pub grid: Rgb,
+  pub live_cell: Rgb,
+  /// Trail left by the previous generation.
+  pub trail_newest: Rgb,
+  /// Color the trail fades to, reached just after the oldest generation
+  /// remembered.
+  pub trail_oldest: Rgb,
+}
+
+impl Theme {
+  /// Color of the trail left by the generation `gen_index` generations ago,
+  /// out of `num_gens` remembered ones.
+  pub fn trail_color(&self, gen_index: usize, num_gens: usize) -> Rgb {
+    mix(
+      self.trail_newest,
+      self.trail_oldest,
+      gen_index as f64 / num_gens as f64,
+    )
+  }
+
+  pub fn color(&self, color: ThemeColor) -> Rgb {
+    match color {
+      ThemeColor::Background => self.background,
+      ThemeColor::Grid => self.grid,
+      ThemeColor::LiveCell => self.live_cell,
+      ThemeColor::TrailNewest => self.trail_newest,
+      ThemeColor::TrailOldest => self.trail_oldest,
+    }
+  }
+
+  pub fn with_color(self, color: ThemeColor, value: Rgb) -> Self {
+    let mut theme = self;
+    match color {
+      ThemeColor::Background => theme.background = value,
+      ThemeColor::Grid => theme.grid = value,
+      ThemeColor::LiveCell => theme.live_cell = value,
+      ThemeColor::TrailNewest => theme.trail_newest = value,
+      ThemeColor::TrailOldest => theme.trail_oldest = value,
+    }
+    theme
+  }
+}
+
+/// One of the colors of a theme, to edit them one by one.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ThemeColor {
+  Background,
+  Grid,
+  LiveCell,
+  TrailNewest,
+  TrailOldest,
+}
+
+impl ThemeColor {
+  pub const ALL: [ThemeColor; 5] = [
+    ThemeColor::Background,
+    ThemeColor::Grid,
+    ThemeColor::LiveCell,
+    ThemeColor::TrailNewest,
+    ThemeColor::TrailOldest,
+  ];
+
+  pub fn label(self) -> &'static str {
+    match self {
+      ThemeColor::Background => "Background",
+      ThemeColor::Grid => "Grid",
+      ThemeColor::LiveCell => "Live cells",
+      ThemeColor::TrailNewest => "Recent trail",
+      ThemeColor::TrailOldest => "Old trail",
+    }
+  }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Preset {
+  Light,
+  Dark,
+  HighContrast,
+  GollyClassic,
+}
+
+impl Preset {
+  pub const ALL: [Preset; 4] = [
+    Preset::Light,
+    Preset::Dark,
+    Preset::HighContrast,
+    Preset::GollyClassic,
+  ];
+
+  pub fn label(self) -> &'static str {
+    match self {
+      Preset::Light => "Light",
+      Preset::Dark => "Dark",
+      Preset::HighContrast => "High contrast",
+      Preset::GollyClassic => "Golly classic",
+    }
+  }
+
+  pub fn theme(self) -> Theme {
+    match self {
+      Preset::Light => Theme {
+        background: BACKGROUND_COLOR,
+        grid: grey_rgb(GRID_GREY),
+        live_cell: LIVE_CELL_COLOR,
+        trail_newest: grey_rgb(0.80),
+        trail_oldest: grey_rgb(0.99),
+      },
+      Preset::Dark => Theme {
+        background: [0x12, 0x12, 0x1a],
+        grid: [0x2a, 0x2a, 0x36],
+        live_cell: [0x8a, 0xb4, 0xf8],
+        trail_newest: [0x3c, 0x3c, 0x50],
+        trail_oldest: [0x16, 0x16, 0x20],
+      },
+      Preset::HighContrast => Theme {
+        background: [0x00, 0x00, 0x00],
+        grid: [0x40, 0x40, 0x40],
+        live_cell: [0xff, 0xff, 0x00],
+        trail_newest: [0x80, 0x80, 0x80],
+        trail_oldest: [0x10, 0x10, 0x10],
+      },
+      // The default colors of Golly.
+      Preset::GollyClassic => Theme {
+        background: [0x30, 0x30, 0x30],
+        grid: [0x60, 0x60, 0x60],
+        live_cell: [0xff, 0xff, 0xff],
+        trail_newest: [0x80, 0x80, 0x80],
+        trail_oldest: [0x38, 0x38, 0x38],
+      },
+    }
+  }
+
+  /// The preset with exactly these colors, if any.
+  pub fn matching(theme: &Theme) -> Option<Preset> {
+    Preset::ALL
+      .iter()
+      .copied()
+      .find(|preset| preset.theme() == *theme)
+  }
+}
+
+/// Theme chosen in the settings.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ThemeChoice {
+  /// The light or the dark preset, following the color scheme preferred by
+  /// the user (`prefers-color-scheme`).
+  #[default]
+  System,
+  Custom(Theme),
+}
+
+impl ThemeChoice {
+  pub fn theme(&self, prefers_dark: bool) -> Theme {
+    match self {
+      ThemeChoice::System if prefers_dark => Preset::Dark.theme(),
+      ThemeChoice::System => Preset::Light.theme(),
+      ThemeChoice::Custom(theme) => *theme,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn light_trail_fades_like_the_grey_trail() {
+    let theme = Preset::Light.theme();
+    assert_eq!(theme.trail_color(0, 10), [204, 204, 204]);
+    assert_eq!(theme.trail_color(5, 10), [228, 228, 228]);
+  }
+
+  #[test]
+  fn system_choice_follows_the_preferred_color_scheme() {
+    assert_eq!(ThemeChoice::System.theme(false), Preset::Light.theme());
+    assert_eq!(ThemeChoice::System.theme(true), Preset::Dark.theme());
+    let custom = Preset::Dark
+      .theme()
+      .with_color(ThemeColor::LiveCell, [1, 2, 3]);
+    assert_eq!(
+      ThemeChoice::Custom(custom).theme(false).live_cell,
+      [1, 2, 3]
+    );
+    assert_eq!(Preset::matching(&custom), None);
+    assert_eq!(
+      Preset::matching(&Preset::GollyClassic.theme()),
+      Some(Preset::GollyClassic)
+    );
+  }
+}
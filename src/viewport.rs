@@ -0,0 +1,166 @@
+//! Conversions between canvas pixels and cells, for a given offset and zoom.
+//! A cell `(x, y)` covers the point `(x, y)` to `(x + 1, y + 1)` in cell
+//! coordinates.
+
+use crate::export::Area;
+use crate::lexicon::Cell;
+use crate::settings::Settings;
+
+/// Far enough to see about a million cells per pixel with the default cell
+/// size.
+pub const MIN_ZOOM: f64 = 1.0 / (1 << 24) as f64;
+
+/// Width of the grid lines, in pixels. Below the zoom at which the grid is
+/// drawn, the space left for it shrinks with the cells, so that cells can get
+/// smaller than a pixel.
+pub fn grid_width(zoom: f64, settings: &Settings) -> f64 {
+  settings.grid_width * f64::min(1.0, zoom / settings.grid_min_zoom)
+}
+
+/// Size of a cell and of the grid line next to it, in pixels.
+pub fn pitch(zoom: f64, settings: &Settings) -> f64 {
+  zoom * settings.cell_size + grid_width(zoom, settings)
+}
+
+/// Zoom at which the pitch is `pitch`, the inverse of `pitch()`.
+pub fn zoom_for_pitch(pitch: f64, settings: &Settings) -> f64 {
+  let grid_min_pitch = settings.grid_min_zoom * settings.cell_size + settings.grid_width;
+  if pitch >= grid_min_pitch {
+    (pitch - settings.grid_width) / settings.cell_size
+  } else {
+    pitch * settings.grid_min_zoom / grid_min_pitch
+  }
+}
+
+/// Area of the board visible in a canvas of the given size.
+pub fn visible_area(
+  offset: (f64, f64),
+  zoom: f64,
+  (width, height): (u32, u32),
+  settings: &Settings,
+) -> Area {
+  let (pitch, grid_width) = (pitch(zoom, settings), grid_width(zoom, settings));
+  Area {
+    x: -(offset.0 + grid_width) / pitch,
+    y: -(offset.1 + grid_width) / pitch,
+    width: width as f64 / pitch,
+    height: height as f64 / pitch,
+  }
+}
+
+/// Offset putting the point `center` (in cell coordinates) at the center of
+/// the canvas.
+pub fn offset_centered_on(
+  center: (f64, f64),
+  zoom: f64,
+  (width, height): (u32, u32),
+  settings: &Settings,
+) -> (f64, f64) {
+  let (pitch, grid_width) = (pitch(zoom, settings), grid_width(zoom, settings));
+  (
+    width as f64 / 2_f64 - grid_width - pitch * center.0,
+    height as f64 / 2_f64 - grid_width - pitch * center.1,
+  )
+}
+
+/// Zoom at which the cells in `bounds`, plus `margin` cells on each side, fit
+/// in the canvas.
+pub fn zoom_to_fit(
+  (min, max): (Cell, Cell),
+  margin: i32,
+  (width, height): (u32, u32),
+  settings: &Settings,
+) -> f64 {
+  let columns = (max.x - min.x + 1 + 2 * margin) as f64;
+  let rows = (max.y - min.y + 1 + 2 * margin) as f64;
+  let pitch = f64::min(width as f64 / columns, height as f64 / rows);
+  zoom_for_pitch(pitch, settings).clamp(MIN_ZOOM, settings.max_zoom)
+}
+
+/// Center of the cells in `bounds`, in cell coordinates.
+pub fn center_of((min, max): (Cell, Cell)) -> (f64, f64) {
+  (
+    (min.x + max.x + 1) as f64 / 2_f64,
+    (min.y + max.y + 1) as f64 / 2_f64,
+  )
+}
+
+/// Offset and settings to draw in device pixels, on a screen with `ratio`
+/// device pixels per CSS pixel. The offset is rounded to whole device pixels,
+/// so that a pattern is drawn the same whatever the fraction of the offset.
+pub fn to_device_pixels(
+  offset: (f64, f64),
+  settings: &Settings,
+  ratio: f64,
+) -> ((f64, f64), Settings) {
+  let settings = Settings {
+    cell_size: settings.cell_size * ratio,
+    grid_width: settings.grid_width * ratio,
+    ..settings.clone()
+  };
+  (
+    ((offset.0 * ratio).round(), (offset.1 * ratio).round()),
+    settings,
+  )
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::settings::default_settings;
+
+  #[test]
+  fn offset_centered_on_is_inverse_of_visible_area() {
+    let settings = default_settings();
+    let offset = offset_centered_on((10.0, -4.0), 2.0, (800, 600), &settings);
+    let area = visible_area(offset, 2.0, (800, 600), &settings);
+    assert!((area.x + area.width / 2.0 - 10.0).abs() < 1e-9);
+    assert!((area.y + area.height / 2.0 + 4.0).abs() < 1e-9);
+  }
+
+  #[test]
+  fn zoom_to_fit_frames_bounds_and_margin() {
+    let settings = default_settings();
+    let bounds = (Cell { x: 0, y: 0 }, Cell { x: 37, y: 9 });
+    let zoom = zoom_to_fit(bounds, 1, (820, 600), &settings);
+    let area = visible_area((0.0, 0.0), zoom, (820, 600), &settings);
+    assert!((area.width - 40.0).abs() < 1e-9);
+  }
+
+  #[test]
+  fn zoom_for_pitch_is_inverse_of_pitch() {
+    let settings = default_settings();
+    for &zoom in &[2.0, settings.grid_min_zoom, 0.1, 1e-4] {
+      let pitch = pitch(zoom, &settings);
+      assert!((zoom_for_pitch(pitch, &settings) - zoom).abs() < 1e-12);
+    }
+  }
+
+  #[test]
+  fn to_device_pixels_scales_the_pitch() {
+    let settings = default_settings();
+    let (offset, device_settings) = to_device_pixels((10.3, -4.0), &settings, 2.0);
+    assert_eq!(offset, (21.0, -8.0));
+    for &zoom in &[2.0, 0.1] {
+      assert!((pitch(zoom, &device_settings) - 2.0 * pitch(zoom, &settings)).abs() < 1e-12);
+    }
+  }
+
+  #[test]
+  fn zoom_to_fit_stays_within_limits() {
+    let settings = default_settings();
+    let cell = Cell { x: 0, y: 0 };
+    assert_eq!(
+      zoom_to_fit((cell, cell), 0, (800, 600), &settings),
+      settings.max_zoom
+    );
+    let huge = (
+      cell,
+      Cell {
+        x: i32::MAX / 2,
+        y: 0,
+      },
+    );
+    assert_eq!(zoom_to_fit(huge, 0, (800, 600), &settings), MIN_ZOOM);
+  }
+}
@@ -0,0 +1,204 @@
+//! Compares `render::pixels::draw_board` with `render::canvas::draw_board`,
+//! which only runs in a browser:
+//!
+//! ```sh
+//! wasm-pack test --headless --firefox --release --test render
+//! ```
+//!
+//! Both must draw the same pixels, and the timings of both are logged to the
+//! console.
+
+#![cfg(target_arch = "wasm32")]
+
+use wasm_bindgen::JsCast;
+use wasm_bindgen_test::{console_log, wasm_bindgen_test, wasm_bindgen_test_configure};
+use web_sys::{CanvasRenderingContext2d, HtmlCanvasElement};
+use yew_app::export::RgbaImage;
+use yew_app::lexicon::Cell;
+use yew_app::life::{tick, CellSet};
+use yew_app::render::color_mode::{ColorMode, Layers};
+use yew_app::render::{canvas, pixels};
+use yew_app::settings::{default_settings, Settings};
+use yew_app::soup::{soup, SoupOptions};
+use yew_app::theme::{Preset, Theme};
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+const WIDTH: u32 = 1280;
+const HEIGHT: u32 = 800;
+const FRAMES: u32 = 50;
+
+/// Largest difference, per channel, allowed where a rectangle edge falls
+/// inside a pixel: browsers round the coverage of such pixels to 8 bits,
+/// while `RgbaImage::blend_rect` blends with the exact coverage.
+const ANTIALIASING_TOLERANCE: u8 = 8;
+
+/// A grid of 8 × 8 soups, with a trail of the given length.
+fn board(trail_length: usize) -> (CellSet, Vec<CellSet>) {
+  let options = SoupOptions {
+    size: 32,
+    ..SoupOptions::default()
+  };
+  let mut cells = CellSet::new();
+  for i in 0..64 {
+    let (dx, dy) = (i % 8 * 40, i / 8 * 40);
+    cells.extend(soup(&i.to_string(), &options).iter().map(|cell| Cell {
+      x: cell.x + dx,
+      y: cell.y + dy,
+    }));
+  }
+  let mut previous_gens = vec![];
+  for _ in 0..trail_length {
+    let next = tick(&cells);
+    previous_gens.insert(0, std::mem::replace(&mut cells, next));
+  }
+  (cells, previous_gens)
+}
+
+fn context() -> CanvasRenderingContext2d {
+  let document = web_sys::window().unwrap().document().unwrap();
+  let canvas: HtmlCanvasElement = document.create_element("canvas").unwrap().unchecked_into();
+  canvas.set_width(WIDTH);
+  canvas.set_height(HEIGHT);
+  canvas.get_context("2d").unwrap().unwrap().unchecked_into()
+}
+
+struct Renderers {
+  context: CanvasRenderingContext2d,
+  image: RgbaImage,
+  cells: CellSet,
+  layers: Layers,
+  settings: Settings,
+  theme: Theme,
+}
+
+impl Renderers {
+  fn new(settings: Settings) -> Self {
+    let theme = Preset::Dark.theme();
+    let (cells, previous_gens) = board(settings.trail_length);
+    let layers = ColorMode::Trail.layers(&cells, &previous_gens, &settings, &theme);
+    Renderers {
+      context: context(),
+      image: RgbaImage::new(WIDTH, HEIGHT, theme.background),
+      cells,
+      layers,
+      settings,
+      theme,
+    }
+  }
+
+  fn draw_canvas(&self, offset: (f64, f64), zoom: f64) {
+    canvas::draw_board(
+      &self.context,
+      (WIDTH, HEIGHT),
+      &self.cells,
+      &self.layers,
+      offset,
+      zoom,
+      &self.settings,
+      &self.theme,
+    );
+  }
+
+  fn draw_pixels(&mut self, offset: (f64, f64), zoom: f64) {
+    pixels::draw_board(
+      &mut self.image,
+      &self.cells,
+      &self.layers,
+      offset,
+      zoom,
+      &self.settings,
+      &self.theme,
+    );
+  }
+
+  /// Waits for the canvas to have drawn everything, by reading a pixel back.
+  fn flush(&self) {
+    self.context.get_image_data(0.0, 0.0, 1.0, 1.0).unwrap();
+  }
+
+  /// Largest difference, per channel, between the pixels drawn by both.
+  fn difference(&mut self, offset: (f64, f64), zoom: f64) -> u8 {
+    self.draw_canvas(offset, zoom);
+    self.draw_pixels(offset, zoom);
+    let canvas = self
+      .context
+      .get_image_data(0.0, 0.0, WIDTH as f64, HEIGHT as f64)
+      .unwrap()
+      .data();
+    canvas
+      .iter()
+      .zip(&self.image.pixels)
+      .map(|(&a, &b)| a.abs_diff(b))
+      .max()
+      .unwrap_or(0)
+  }
+}
+
+#[wasm_bindgen_test]
+fn renderers_draw_identical_pixels_at_whole_pixel_positions() {
+  // Whole grid widths and cell sizes, so that every edge is on a pixel
+  // boundary.
+  let mut renderers = Renderers::new(Settings {
+    cell_size: 4.0,
+    grid_width: 1.0,
+    grid_min_zoom: 0.25,
+    ..default_settings()
+  });
+  for (offset, zoom) in [
+    ((0.0, 0.0), 1.0),
+    ((-37.0, 12.0), 2.0),
+    ((5.0, -5.0), 0.25),
+    ((0.0, 0.0), 0.01),
+  ] {
+    assert_eq!(renderers.difference(offset, zoom), 0, "at zoom {}", zoom);
+  }
+}
+
+#[wasm_bindgen_test]
+fn renderers_draw_the_same_pixels_at_fractional_positions() {
+  let mut renderers = Renderers::new(default_settings());
+  for (offset, zoom) in [
+    ((3.3, -2.7), 1.37),
+    ((0.45, -0.2), 0.77),
+    ((1.01, 2.02), 3.33),
+  ] {
+    let difference = renderers.difference(offset, zoom);
+    assert!(
+      difference <= ANTIALIASING_TOLERANCE,
+      "difference of {} at zoom {}",
+      difference,
+      zoom
+    );
+  }
+}
+
+#[wasm_bindgen_test]
+fn renderers_side_by_side() {
+  let mut renderers = Renderers::new(default_settings());
+  for (name, zoom) in [("zoomed in", 4.0), ("default zoom", 1.0), ("far out", 0.01)] {
+    let start = js_sys::Date::now();
+    for _ in 0..FRAMES {
+      renderers.draw_canvas((0.0, 0.0), zoom);
+    }
+    renderers.flush();
+    let canvas_millis = (js_sys::Date::now() - start) / FRAMES as f64;
+
+    let start = js_sys::Date::now();
+    for _ in 0..FRAMES {
+      renderers.draw_pixels((0.0, 0.0), zoom);
+      canvas::put_image(&renderers.context, &renderers.image);
+    }
+    renderers.flush();
+    let pixels_millis = (js_sys::Date::now() - start) / FRAMES as f64;
+
+    console_log!(
+      "{:>12}: canvas {:.2} ms, pixels {:.2} ms per {}×{} frame",
+      name,
+      canvas_millis,
+      pixels_millis,
+      WIDTH,
+      HEIGHT
+    );
+  }
+}